@@ -8,6 +8,7 @@ pub struct GetNotificationReadsUseCase<R: NotificationReadRepository> {
 impl<R: NotificationReadRepository> GetNotificationReadsUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetNotificationReadsUseCase", skip_all)]
     pub async fn execute(&self, phone: &str, business_ids: &[String]) -> Result<Vec<String>, NotificationReadRepoError> {
         self.repo.find_by_phone_and_business_ids(phone, business_ids).await
     }
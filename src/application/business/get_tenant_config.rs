@@ -0,0 +1,39 @@
+use crate::config::ConfigHandle;
+use crate::domain::{BusinessRepository, BusinessRepoError, TenantConfig};
+
+/// Configuración efectiva del tenant: overrides de `[tenants]` > `notificationsConfig` del business > valores globales
+#[derive(Clone)]
+pub struct GetTenantConfigUseCase<R: BusinessRepository> {
+    repo: R,
+    config: ConfigHandle,
+}
+
+impl<R: BusinessRepository> GetTenantConfigUseCase<R> {
+    pub fn new(repo: R, config: ConfigHandle) -> Self {
+        Self { repo, config }
+    }
+
+    /// Nunca falla: si el business no se puede leer se usan los overrides y los valores globales
+    #[tracing::instrument(name = "GetTenantConfigUseCase", skip_all)]
+    pub async fn execute(&self, business_id: &str) -> TenantConfig {
+        let business = match self.repo.find_by_id(business_id).await {
+            Ok(business) => Some(business),
+            Err(BusinessRepoError::NotFound) => {
+                tracing::warn!(business_id, "Business not found, using default tenant config");
+                None
+            }
+            Err(e) => {
+                tracing::warn!(repository = "MongoBusinessRepository", business_id, error = %e, "Error fetching business");
+                None
+            }
+        };
+
+        let tenants = &self.config.current().tenants;
+        TenantConfig::resolve(
+            business_id,
+            business.as_ref(),
+            tenants.override_for(business_id).as_ref(),
+            &tenants.defaults(),
+        )
+    }
+}
@@ -1,4 +1,3 @@
-pub mod get_business;
-
-pub use get_business::GetBusinessUseCase;
+pub mod get_tenant_config;
 
+pub use get_tenant_config::GetTenantConfigUseCase;
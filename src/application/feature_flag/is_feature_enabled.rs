@@ -0,0 +1,31 @@
+use crate::domain::FeatureFlagRepository;
+use crate::types::AuthContext;
+
+#[derive(Clone)]
+pub struct IsFeatureEnabledUseCase<R: FeatureFlagRepository> {
+    repo: R,
+}
+
+impl<R: FeatureFlagRepository> IsFeatureEnabledUseCase<R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+
+    /// Evalúa el flag para el usuario autenticado (business, tipo de cuenta y porcentaje)
+    /// Devuelve `default` si el flag no existe o no se pueden leer los flags
+    pub async fn execute(&self, key: &str, auth_ctx: &AuthContext, default: bool) -> bool {
+        let flags = match self.repo.find_all().await {
+            Ok(flags) => flags,
+            Err(e) => {
+                tracing::warn!(repository = "MongoFeatureFlagRepository", flag = key, error = %e, "Error fetching feature flags");
+                return default;
+            }
+        };
+
+        flags
+            .iter()
+            .find(|flag| flag.key == key)
+            .map(|flag| flag.is_enabled_for(&auth_ctx.business_id, auth_ctx.account_type_id.as_deref(), &auth_ctx.user_id))
+            .unwrap_or(default)
+    }
+}
@@ -0,0 +1,17 @@
+use crate::domain::{FeatureFlag, FeatureFlagRepository, FeatureFlagRepoError};
+
+#[derive(Clone)]
+pub struct ListFeatureFlagsUseCase<R: FeatureFlagRepository> {
+    repo: R,
+}
+
+impl<R: FeatureFlagRepository> ListFeatureFlagsUseCase<R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+
+    #[tracing::instrument(name = "ListFeatureFlagsUseCase", skip_all)]
+    pub async fn execute(&self) -> Result<Vec<FeatureFlag>, FeatureFlagRepoError> {
+        self.repo.find_all().await
+    }
+}
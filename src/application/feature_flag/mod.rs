@@ -0,0 +1,7 @@
+pub mod is_feature_enabled;
+pub mod list_feature_flags;
+pub mod toggle_feature_flag;
+
+pub use is_feature_enabled::IsFeatureEnabledUseCase;
+pub use list_feature_flags::ListFeatureFlagsUseCase;
+pub use toggle_feature_flag::ToggleFeatureFlagUseCase;
@@ -0,0 +1,24 @@
+use crate::domain::{FeatureFlag, FeatureFlagRepository, FeatureFlagRepoError, FeatureFlagUpdate};
+
+#[derive(Clone)]
+pub struct ToggleFeatureFlagUseCase<R: FeatureFlagRepository> {
+    repo: R,
+}
+
+impl<R: FeatureFlagRepository> ToggleFeatureFlagUseCase<R> {
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+
+    #[tracing::instrument(name = "ToggleFeatureFlagUseCase", skip_all, fields(flag = %key))]
+    pub async fn execute(&self, key: &str, update: &FeatureFlagUpdate) -> Result<FeatureFlag, FeatureFlagRepoError> {
+        let flag = self.repo.upsert(key, update).await?;
+        tracing::info!(
+            flag = %flag.key,
+            enabled = flag.enabled,
+            percentage = flag.percentage,
+            "Feature flag updated"
+        );
+        Ok(flag)
+    }
+}
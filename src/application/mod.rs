@@ -3,9 +3,10 @@ pub mod session;
 pub mod user;
 pub mod analytics;
 pub mod business;
+pub mod feature_flag;
 
 pub use session::GetSessionUseCase;
 pub use user::{GetUserUseCase, GetUserByBusinessIdsUseCase, GetUsersUseCase};
 pub use analytics::GetNotificationReadsUseCase;
-pub use business::GetBusinessUseCase;
-
+pub use business::GetTenantConfigUseCase;
+pub use feature_flag::{IsFeatureEnabledUseCase, ListFeatureFlagsUseCase, ToggleFeatureFlagUseCase};
@@ -0,0 +1,48 @@
+use crate::domain::NotificationEvent;
+use crate::types::{AuthContext, DeviceInfo};
+use crate::infrastructure::external::queue::{QueueService, TrackNotificationParams, TrackNotificationEventParams, QueueRequestHeaders};
+
+#[derive(Clone)]
+pub struct EnqueueNotificationEventUseCase {
+    queue_service: QueueService,
+}
+
+impl EnqueueNotificationEventUseCase {
+    pub fn new(queue_service: QueueService) -> Self {
+        Self { queue_service }
+    }
+
+    /// Encola un evento de engagement (delivered, opened, clicked, dismissed) de una notificación
+    #[tracing::instrument(name = "EnqueueNotificationEventUseCase", skip_all)]
+    pub async fn execute(
+        &self,
+        notification_id: &str,
+        auth_ctx: &AuthContext,
+        event: &NotificationEvent,
+        occurred_at: Option<i64>,
+        device: &DeviceInfo,
+        headers: QueueRequestHeaders,
+    ) -> Result<(), String> {
+        let target_url = match event {
+            NotificationEvent::Clicked { target_url } => Some(target_url.clone()),
+            _ => None,
+        };
+
+        let params = TrackNotificationEventParams {
+            track: TrackNotificationParams::new(
+                notification_id,
+                Some(auth_ctx.business_id.clone()),
+                Some(auth_ctx.user_id.clone()),
+                auth_ctx.session_id.clone(),
+                device,
+            ),
+            event: event.name().to_string(),
+            target_url,
+            occurred_at,
+        };
+
+        self.queue_service
+            .enqueue_notification_event(event, params, headers)
+            .await
+    }
+}
@@ -1,4 +1,5 @@
 use crate::infrastructure::external::queue::{QueueService, TrackNotificationParams, QueueRequestHeaders};
+use crate::types::DeviceInfo;
 
 #[derive(Clone)]
 #[allow(dead_code)] // Se usa cuando el tracking está activo en el controlador
@@ -12,23 +13,23 @@ impl EnqueueTrackNotificationUseCase {
     }
 
     #[allow(dead_code)] // Se usa cuando el tracking está activo en el controlador
+    #[tracing::instrument(name = "EnqueueTrackNotificationUseCase", skip_all)]
     pub async fn execute(
         &self,
         notification_id: &str,
         account_id: &str,
         business_id: Option<String>,
         session_id: Option<String>,
+        device: &DeviceInfo,
         headers: QueueRequestHeaders,
     ) -> Result<(), String> {
-        let params = TrackNotificationParams {
-            id: notification_id.to_string(),
+        let params = TrackNotificationParams::new(
+            notification_id,
             business_id,
-            account_id: Some(account_id.to_string()),
-            device_client_type: headers.x_client_device.clone(),
-            device_client_model: headers.x_client_device.clone(),
-            device_client_os: headers.x_client_os.clone(),
+            Some(account_id.to_string()),
             session_id, // Extraído del token JWT
-        };
+            device,
+        );
 
         self.queue_service
             .enqueue_track_notification(params, headers)
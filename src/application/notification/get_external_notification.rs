@@ -9,6 +9,7 @@ pub struct GetGetStreamMessageUseCase<R: GetStreamRepository> {
 impl<R: GetStreamRepository> GetGetStreamMessageUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetGetStreamMessageUseCase", skip_all)]
     pub async fn execute(&self, id: &str, user_id: &str, language: &str, business_id: &str) -> Result<Notification, GetStreamRepoError> {
         self.repo.find_message_by_uuid(id, user_id, language, business_id).await
     }
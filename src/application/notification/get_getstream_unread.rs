@@ -8,6 +8,7 @@ pub struct GetGetStreamUnreadCountUseCase<R: GetStreamRepository> {
 impl<R: GetStreamRepository> GetGetStreamUnreadCountUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetGetStreamUnreadCountUseCase", skip_all)]
     pub async fn execute(&self, user_id: &str) -> Result<i32, GetStreamRepoError> {
         self.repo.get_unread_count(user_id).await
     }
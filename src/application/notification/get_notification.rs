@@ -8,6 +8,7 @@ pub struct GetNotificationUseCase<R: NotificationRepository> {
 impl<R: NotificationRepository> GetNotificationUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetNotificationUseCase", skip_all)]
     pub async fn execute(&self, id: &str, language: &str, business_id: &str) -> Result<Notification, NotificationRepoError> {
         self.repo.find_by_id(id, language, business_id).await
     }
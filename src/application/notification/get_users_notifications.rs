@@ -8,6 +8,7 @@ pub struct GetUsersNotificationsUseCase<R: NotificationRepository> {
 impl<R: NotificationRepository> GetUsersNotificationsUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetUsersNotificationsUseCase", skip_all)]
     pub async fn execute(&self, users: &[SimplifiedUser], business_ids: &[String]) -> Result<Vec<String>, NotificationRepoError> {
         self.repo.find_users_notifications(users, business_ids).await
     }
@@ -3,10 +3,12 @@ pub mod get_users_notifications;
 pub mod get_external_notification;
 pub mod get_getstream_unread;
 pub mod enqueue_track_notification;
+pub mod enqueue_notification_event;
 
 pub use get_notification::GetNotificationUseCase;
 pub use get_users_notifications::GetUsersNotificationsUseCase;
 pub use get_external_notification::GetGetStreamMessageUseCase;
 pub use get_getstream_unread::GetGetStreamUnreadCountUseCase;
 pub use enqueue_track_notification::EnqueueTrackNotificationUseCase;
+pub use enqueue_notification_event::EnqueueNotificationEventUseCase;
 
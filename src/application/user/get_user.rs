@@ -8,6 +8,7 @@ pub struct GetUserUseCase<R: UserRepository> {
 impl<R: UserRepository> GetUserUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetUserUseCase", skip_all)]
     pub async fn execute(&self, id: &str, business_id: &str) -> Result<SimplifiedUser, UserRepoError> {
         self.repo.find_simplified_by_id(id, business_id).await
     }
@@ -8,6 +8,7 @@ pub struct GetUserByBusinessIdsUseCase<R: UserRepository> {
 impl<R: UserRepository> GetUserByBusinessIdsUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetUserByBusinessIdsUseCase", skip_all)]
     pub async fn execute(&self, id: &str, business_ids: &[String]) -> Result<SimplifiedUser, UserRepoError> {
         self.repo.find_by_id_and_business_ids(id, business_ids).await
     }
@@ -8,6 +8,7 @@ pub struct GetUsersUseCase<R: UserRepository> {
 impl<R: UserRepository> GetUsersUseCase<R> {
     pub fn new(repo: R) -> Self { Self { repo } }
 
+    #[tracing::instrument(name = "GetUsersUseCase", skip_all)]
     pub async fn execute(&self, phone: &str, business_ids: &[String]) -> Result<Vec<SimplifiedUser>, UserRepoError> {
         self.repo.find_by_phone_and_business_ids(phone, business_ids).await
     }
@@ -0,0 +1,51 @@
+use actix_web::{HttpResponse, Responder};
+use crate::infrastructure::services::AppServices;
+use crate::mappers::feature_flag::{domain_to_dto, update_request_to_domain, FeatureFlagDto, FeatureFlagUpdateRequest};
+use crate::response::ApiResponse;
+use super::AdminController;
+
+/// Keys en snake_case ("aggregated_badge"): se usan como constantes en el código
+fn is_valid_key(key: &str) -> bool {
+    (1..=64).contains(&key.len())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '.' | '-'))
+}
+
+impl AdminController {
+    /// Lista todos los feature flags con su estado actual en Mongo
+    pub async fn list_feature_flags(services: actix_web::web::Data<AppServices>) -> impl Responder {
+        match services.feature_flags.list.execute().await {
+            Ok(flags) => {
+                let flags: Vec<FeatureFlagDto> = flags.into_iter().map(domain_to_dto).collect();
+                HttpResponse::Ok().json(ApiResponse::ok(flags))
+            }
+            Err(e) => {
+                tracing::error!(repository = "MongoFeatureFlagRepository", error = %e, "Error listing feature flags");
+                HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Could not list feature flags"))
+            }
+        }
+    }
+
+    /// Activa o desactiva un flag (y opcionalmente cambia su porcentaje); lo crea si no existe
+    pub async fn toggle_feature_flag(
+        services: actix_web::web::Data<AppServices>,
+        key: String,
+        body: FeatureFlagUpdateRequest,
+    ) -> impl Responder {
+        if !is_valid_key(&key) {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Invalid flag key, expected lowercase letters, digits, '_', '.' or '-'"));
+        }
+        let update = match update_request_to_domain(&body) {
+            Ok(update) => update,
+            Err(msg) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(msg)),
+        };
+
+        match services.feature_flags.toggle.execute(&key, &update).await {
+            Ok(flag) => HttpResponse::Ok().json(ApiResponse::ok(domain_to_dto(flag))),
+            Err(e) => {
+                tracing::error!(repository = "MongoFeatureFlagRepository", flag = %key, error = %e, "Error updating feature flag");
+                HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Could not update feature flag"))
+            }
+        }
+    }
+}
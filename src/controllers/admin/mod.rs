@@ -0,0 +1,4 @@
+pub mod feature_flags;
+pub mod resolve_storage_key;
+
+pub use resolve_storage_key::AdminController;
@@ -0,0 +1,62 @@
+use actix_web::{HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use crate::infrastructure::services::AppServices;
+use crate::response::ApiResponse;
+
+pub struct AdminController;
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+pub struct ResolveStorageKeyQuery {
+    pub path: String,
+    pub businessId: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize)]
+pub struct StorageKeyResolutionDto {
+    pub path: String,
+    pub businessId: Option<String>,
+    pub key: String,
+    /// None = bucket por defecto del backend
+    pub bucket: Option<String>,
+    pub ruleIndex: Option<usize>,
+    pub rule: Option<String>,
+    /// CDN del tenant con el que se construiría la URL (None = estrategia configurada del business)
+    pub cdnBaseUrl: Option<String>,
+}
+
+impl AdminController {
+    /// Dry-run de las reglas de reescritura de keys: muestra cómo se resuelve un path sin firmar nada
+    /// Con `businessId` se resuelve igual que las imágenes de sus notificaciones (bucket y CDN del tenant)
+    pub async fn resolve_storage_key(
+        services: actix_web::web::Data<AppServices>,
+        query: ResolveStorageKeyQuery,
+    ) -> impl Responder {
+        if query.path.trim().is_empty() {
+            return HttpResponse::BadRequest().json(ApiResponse::<()>::error("path is required"));
+        }
+
+        let business_id = query.businessId.filter(|id| !id.is_empty());
+        let tenant = match &business_id {
+            Some(id) => Some(services.business.get_tenant_config.execute(id).await),
+            None => None,
+        };
+        let resolution = services.storage.resolve_for_tenant(tenant.as_ref(), &query.path);
+        let cdn_base_url = services.storage.tenant_cdn(tenant.as_ref()).map(str::to_string);
+        let (rule_index, rule) = match resolution.rule {
+            Some((index, description)) => (Some(index), Some(description)),
+            None => (None, None),
+        };
+
+        HttpResponse::Ok().json(ApiResponse::ok(StorageKeyResolutionDto {
+            path: query.path,
+            businessId: business_id,
+            key: resolution.object.key,
+            bucket: resolution.object.bucket,
+            ruleIndex: rule_index,
+            rule,
+            cdnBaseUrl: cdn_base_url,
+        }))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod notification;
+pub use admin::AdminController;
 pub use notification::NotificationController;
-
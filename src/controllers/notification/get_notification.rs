@@ -2,15 +2,24 @@ use actix_web::{HttpRequest, HttpResponse, Responder};
 use actix_web::HttpMessage;
 use crate::infrastructure::services::AppServices;
 use crate::response::ApiResponse;
-use crate::types::AuthContext;
+use crate::domain::TenantConfig;
+use crate::domain::feature_flag::keys as flags;
+use crate::types::{AuthContext, ImageHint};
 use crate::mappers::{notification::domain_to_response, common::sha512_hash};
 use crate::infrastructure::external::queue::QueueRequestHeaders;
+use crate::middleware::device::device_info;
+use crate::metrics::UNREAD_COUNT_DEGRADED;
 
 /// Controlador para endpoints de notificaciones
 pub struct NotificationController;
 
 impl NotificationController {
     /// Obtiene una notificación por ID, incluyendo datos del usuario autenticado
+    #[tracing::instrument(
+        name = "NotificationController::get_notification",
+        skip_all,
+        fields(notification_id = %id, business_id = tracing::field::Empty)
+    )]
     pub async fn get_notification(
         req: HttpRequest,
         services: actix_web::web::Data<AppServices>,
@@ -22,6 +31,7 @@ impl NotificationController {
             Ok(ctx) => ctx,
             Err(response) => return response,
         };
+        tracing::Span::current().record("business_id", business_id.as_str());
 
         // Preparar businessIds para usar en queries
         let business_ids_to_use = if business_ids.is_empty() {
@@ -30,68 +40,79 @@ impl NotificationController {
             business_ids.clone()
         };
 
+        // Configuración del tenant: la resuelve session_guard (cacheada); si no está, se consulta aquí
+        let tenant = Self::tenant_config(&req, &services, &business_id).await;
+        // Toggles del tenant: "badge" (contador de no leídas) y "tracking" (encolar la visualización)
+        let badge_enabled = tenant.feature_enabled("badge", true);
+        // Feature flag (rollout gradual): sumar al badge las no leídas de GetStream
+        let getstream_unread_enabled = badge_enabled
+            && services.feature_flags.is_enabled.execute(flags::GETSTREAM_UNREAD_COUNT, &auth_ctx, true).await;
+
         // Ejecutar queries en paralelo
         let is_mongo_id = mongodb::bson::oid::ObjectId::parse_str(&id).is_ok();
-        let (user_result, notification_result, business_result, getstream_unread_result) = tokio::join!(
-            Self::fetch_user(&services, &auth_ctx.user_id, &business_id, &business_ids),
+        let (user_result, notification_result, getstream_unread_result) = tokio::join!(
+            async {
+                if badge_enabled {
+                    Some(Self::fetch_user(&services, &auth_ctx.user_id, &business_id, &business_ids).await)
+                } else {
+                    None
+                }
+            },
             Self::fetch_notification(&services, &id, is_mongo_id, &auth_ctx.user_id, &language, &business_id),
-            services.business.get_business.execute(&business_id),
-            services.notification.get_getstream_unread_count.execute(&auth_ctx.user_id),
+            async {
+                match (badge_enabled, getstream_unread_enabled) {
+                    (true, true) => Some(services.notification.get_getstream_unread_count.execute(&auth_ctx.user_id).await),
+                    (true, false) => Some(Ok(0)),
+                    (false, _) => None,
+                }
+            },
         );
 
         // Procesar notificación (obligatoria)
         let notification = match notification_result {
             Ok(n) => n,
             Err(e) => {
-                eprintln!("[NotificationController::get_notification] Error fetching notification {}: {:?}", id, e);
+                match e {
+                    crate::domain::NotificationRepoError::NotFound => {
+                        tracing::warn!(error.kind = e.kind(), "Notification not found")
+                    }
+                    _ => tracing::error!(error.kind = e.kind(), error = %e, "Error fetching notification"),
+                }
                 return HttpResponse::NotFound()
                     .json(ApiResponse::<()>::error("Notification not found"));
             }
         };
 
-        // Procesar resultados opcionales
-        let user = user_result.ok();
-        let business = business_result.ok();
-
-        // Obtener datos adicionales para calcular unread count real
-        // Estas queries están optimizadas para ejecutarse rápidamente
-        let getstream_unread_count = getstream_unread_result.unwrap_or(0);
-        
-        let (all_notifications, notification_reads) = if user.is_some() {
-            Self::fetch_additional_data(&services, &user, &business_ids_to_use).await
-        } else {
-            (None, None)
+        let unread_count = match (user_result, getstream_unread_result) {
+            (Some(user_result), Some(getstream_unread_result)) => Some(
+                Self::unread_count(&services, user_result, getstream_unread_result, &business_ids_to_use).await,
+            ),
+            _ => None,
         };
 
-        // Calcular unread count
-        // Si las queries adicionales fallaron o timeout, usamos solo GetStream (que es más rápido)
-        let unread_count = Self::calculate_unread_count(
-            &all_notifications,
-            &notification_reads,
-            getstream_unread_count,
-        );
-
         // Encolar tracking solo si la notificación es de MongoDB (es decir, es una notificación del servidor)
-        if is_mongo_id {
+        if is_mongo_id && tenant.feature_enabled("tracking", true) {
             let tracking_headers = Self::extract_tracking_headers(&req);
+            let device = device_info(&req);
             let _ = services.notification.enqueue_track_notification.execute(
                 &id,
                 &auth_ctx.user_id,
                 Some(business_id.clone()),
                 auth_ctx.session_id.clone(), // Extraer sessionId del token
+                &device,
                 tracking_headers,
             ).await;
             // Ignoramos errores de tracking para no afectar la respuesta principal
         }
 
         // Construir respuesta
-        let business_name = business.map(|b| b.name).unwrap_or_else(|| "Goil".to_string());
         let resp = domain_to_response(
             notification,
-            &services.storage.s3_signer,
-            Some(business_id.clone()),
-            Some(business_name),
+            &services.storage,
+            &tenant,
             unread_count,
+            &Self::extract_image_hint(&req),
+            services.config.current().storage.url_expires_in_secs,
         ).await;
 
         HttpResponse::Ok().json(ApiResponse::ok(resp))
@@ -99,11 +120,15 @@ impl NotificationController {
 
     // Métodos privados de ayuda
 
-    fn extract_context(req: &HttpRequest) -> Result<(String, AuthContext, String), HttpResponse> {
-        let language = req.extensions()
-            .get::<String>()
-            .cloned()
-            .unwrap_or_else(|| "es".to_string());
+    pub(super) fn extract_context(req: &HttpRequest) -> Result<(String, AuthContext, String), HttpResponse> {
+        // Idioma de la sesión (o del tenant) que deja `session_guard`; sin él, el del tenant o el global
+        let language = req.extensions().get::<String>().cloned()
+            .or_else(|| req.extensions().get::<TenantConfig>().map(|tenant| tenant.default_language.clone()))
+            .or_else(|| {
+                req.app_data::<actix_web::web::Data<AppServices>>()
+                    .map(|services| services.config.current().tenants.default_language.clone())
+            })
+            .unwrap_or_default();
         
         let Some(auth_ctx) = req.extensions().get::<AuthContext>().cloned() else {
             return Err(HttpResponse::Forbidden()
@@ -119,6 +144,54 @@ impl NotificationController {
         Ok((language, auth_ctx, business_id))
     }
 
+    pub(super) async fn tenant_config(req: &HttpRequest, services: &AppServices, business_id: &str) -> TenantConfig {
+        let resolved = req.extensions().get::<TenantConfig>().cloned();
+        match resolved {
+            Some(tenant) => tenant,
+            None => services.business.get_tenant_config.execute(business_id).await,
+        }
+    }
+
+    /// Calcula el contador de no leídas (servidor + GetStream)
+    /// Si las queries adicionales fallan se usa solo GetStream (que es más rápido)
+    async fn unread_count(
+        services: &AppServices,
+        user_result: Result<crate::domain::SimplifiedUser, crate::domain::UserRepoError>,
+        getstream_unread_result: Result<i32, impl std::fmt::Display>,
+        business_ids: &[String],
+    ) -> i32 {
+        let user = user_result
+            .inspect_err(|e| tracing::warn!(repository = "MongoUserRepository", error.kind = e.kind(), error = %e, "Error fetching user"))
+            .ok();
+
+        // Obtener datos adicionales para calcular unread count real
+        // Estas queries están optimizadas para ejecutarse rápidamente
+        if let Err(e) = &getstream_unread_result {
+            tracing::warn!(service = "getstream", error = %e, "Unread count computed without GetStream");
+            UNREAD_COUNT_DEGRADED.with_label_values(&["getstream"]).inc();
+        }
+        let getstream_unread_count = getstream_unread_result.unwrap_or(0);
+        
+        let (all_notifications, notification_reads) = if user.is_some() {
+            Self::fetch_additional_data(services, &user, business_ids).await
+        } else {
+            UNREAD_COUNT_DEGRADED.with_label_values(&["user"]).inc();
+            (None, None)
+        };
+        if user.is_some() && all_notifications.is_none() {
+            UNREAD_COUNT_DEGRADED.with_label_values(&["notifications"]).inc();
+        }
+        if user.is_some() && notification_reads.is_none() {
+            UNREAD_COUNT_DEGRADED.with_label_values(&["reads"]).inc();
+        }
+
+        Self::calculate_unread_count(
+            &all_notifications,
+            &notification_reads,
+            getstream_unread_count,
+        )
+    }
+
     async fn fetch_user(
         services: &AppServices,
         user_id: &str,
@@ -171,7 +244,8 @@ impl NotificationController {
         let users_found = match users_result {
             Ok(users) => users,
             Err(e) => {
-                eprintln!("[NotificationController::get_notification] Error fetching users by phone {}: {:?}", phone, e);
+                // El teléfono nunca se registra
+                tracing::error!(repository = "MongoUserRepository", error.kind = e.kind(), error = %e, "Error fetching users by phone");
                 // Si falla obtener usuarios, aún podemos intentar obtener reads con el phone hasheado
                 let notification_reads_result = services.analytics.get_notification_reads.execute(&hashed_phone, business_ids).await;
                 return (None, notification_reads_result.ok());
@@ -225,7 +299,37 @@ impl NotificationController {
         server_unread_count + getstream_unread_count
     }
 
-    fn extract_tracking_headers(req: &HttpRequest) -> QueueRequestHeaders {
+    /// Pista de variante de imagen: query (`density`, `width`) o headers (`x-client-density`, `x-client-image-width`)
+    fn extract_image_hint(req: &HttpRequest) -> ImageHint {
+        let query_param = |name: &str| {
+            req.query_string()
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.trim().to_string())
+        };
+
+        // Densidades como 2.625 (xxhdpi) se redondean hacia arriba para no servir imágenes borrosas
+        let density = query_param("density")
+            .or_else(|| header("x-client-density"))
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|d| d.is_finite() && *d > 0.0)
+            .map(|d| d.ceil().clamp(1.0, 3.0) as u8);
+        let width = query_param("width")
+            .or_else(|| header("x-client-image-width"))
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|w| *w > 0);
+
+        ImageHint { density, width }
+    }
+
+    pub(super) fn extract_tracking_headers(req: &HttpRequest) -> QueueRequestHeaders {
         let authorization = req.headers()
             .get("authorization")
             .and_then(|h| h.to_str().ok())
@@ -1,4 +1,4 @@
 pub mod get_notification;
+pub mod track_event;
 
 pub use get_notification::NotificationController;
-
@@ -0,0 +1,68 @@
+use actix_web::{HttpRequest, HttpResponse, Responder};
+use crate::infrastructure::services::AppServices;
+use crate::response::ApiResponse;
+use crate::mappers::notification::{event_request_to_domain, NotificationEventAckDto, NotificationEventRequest};
+use crate::middleware::device::device_info;
+use super::NotificationController;
+
+impl NotificationController {
+    /// Registra un evento de engagement (delivered, opened, clicked, dismissed) de una notificación
+    #[tracing::instrument(
+        name = "NotificationController::track_event",
+        skip_all,
+        fields(notification_id = %id, business_id = tracing::field::Empty)
+    )]
+    pub async fn track_event(
+        req: HttpRequest,
+        services: actix_web::web::Data<AppServices>,
+        id: String,
+        body: NotificationEventRequest,
+    ) -> impl Responder {
+        let (_language, auth_ctx, business_id) = match Self::extract_context(&req) {
+            Ok(ctx) => ctx,
+            Err(response) => return response,
+        };
+        tracing::Span::current().record("business_id", business_id.as_str());
+
+        // Solo las notificaciones del servidor (MongoDB) se trackean, igual que en get_notification
+        if mongodb::bson::oid::ObjectId::parse_str(&id).is_err() {
+            return HttpResponse::BadRequest()
+                .json(ApiResponse::<()>::error("Invalid notification id"));
+        }
+
+        let event = match event_request_to_domain(&body) {
+            Ok(event) => event,
+            Err(msg) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(msg)),
+        };
+
+        // Con el tracking desactivado para el tenant se acepta el evento sin registrarlo
+        let tenant = Self::tenant_config(&req, &services, &business_id).await;
+        if !tenant.feature_enabled("tracking", true) {
+            tracing::debug!(event = event.name(), "Tracking disabled for business, event not recorded");
+            return HttpResponse::Accepted().json(ApiResponse::ok(NotificationEventAckDto {
+                notificationId: id,
+                event: event.name(),
+            }));
+        }
+
+        let tracking_headers = Self::extract_tracking_headers(&req);
+        let device = device_info(&req);
+        if let Err(e) = services.notification.enqueue_notification_event.execute(
+            &id,
+            &auth_ctx,
+            &event,
+            body.occurred_at,
+            &device,
+            tracking_headers,
+        ).await {
+            tracing::error!(service = "queue", event = event.name(), error = %e, "Error enqueuing notification event");
+            return HttpResponse::BadGateway()
+                .json(ApiResponse::<()>::error("Could not track event"));
+        }
+
+        HttpResponse::Accepted().json(ApiResponse::ok(NotificationEventAckDto {
+            notificationId: id,
+            event: event.name(),
+        }))
+    }
+}
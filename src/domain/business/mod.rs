@@ -1,8 +1,105 @@
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub struct Business {
     pub name: String,
+    /// Configuración de notificaciones guardada en el business (`notificationsConfig`)
+    pub settings: TenantSettings,
+}
+
+/// Valores opcionales de la configuración de un tenant (del business o de los overrides)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TenantSettings {
+    pub brand_name: Option<String>,
+    pub default_language: Option<String>,
+    pub logo_url: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    pub bucket: Option<String>,
+    pub cdn_base_url: Option<String>,
+    pub allowed_platforms: Option<Vec<String>>,
+    pub features: HashMap<String, bool>,
+}
+
+/// Valores globales para los businesses sin configuración propia
+#[derive(Clone, Debug, PartialEq)]
+pub struct TenantDefaults {
+    pub brand_name: String,
+    pub language: String,
+}
+
+/// Configuración efectiva de un tenant (business)
+/// Precedencia: override > configuración del business > nombre del business > valores globales
+#[derive(Clone, Debug, PartialEq)]
+pub struct TenantConfig {
+    pub business_id: String,
+    pub brand_name: String,
+    pub default_language: String,
+    pub logo_url: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    /// Bucket de las imágenes (si las reglas de keys no indican otro)
+    pub bucket: Option<String>,
+    /// CDN público para las imágenes del tenant
+    pub cdn_base_url: Option<String>,
+    /// Sistemas operativos permitidos ("iOS", "Android"...); vacío = todos
+    pub allowed_platforms: Vec<String>,
+    pub features: HashMap<String, bool>,
+}
+
+impl TenantConfig {
+    pub fn resolve(
+        business_id: &str,
+        business: Option<&Business>,
+        overrides: Option<&TenantSettings>,
+        defaults: &TenantDefaults,
+    ) -> Self {
+        let stored = business.map(|b| &b.settings);
+        let pick = |field: fn(&TenantSettings) -> &Option<String>| {
+            overrides
+                .and_then(|o| field(o).clone())
+                .or_else(|| stored.and_then(|s| field(s).clone()))
+        };
+
+        let mut features = stored.map(|s| s.features.clone()).unwrap_or_default();
+        if let Some(overrides) = overrides {
+            features.extend(overrides.features.clone());
+        }
+
+        Self {
+            business_id: business_id.to_string(),
+            brand_name: pick(|s| &s.brand_name)
+                .or_else(|| business.map(|b| b.name.clone()).filter(|name| !name.trim().is_empty()))
+                .unwrap_or_else(|| defaults.brand_name.clone()),
+            default_language: pick(|s| &s.default_language).unwrap_or_else(|| defaults.language.clone()),
+            logo_url: pick(|s| &s.logo_url),
+            primary_color: pick(|s| &s.primary_color),
+            secondary_color: pick(|s| &s.secondary_color),
+            bucket: pick(|s| &s.bucket),
+            cdn_base_url: pick(|s| &s.cdn_base_url),
+            allowed_platforms: overrides
+                .and_then(|o| o.allowed_platforms.clone())
+                .or_else(|| stored.and_then(|s| s.allowed_platforms.clone()))
+                .unwrap_or_default(),
+            features,
+        }
+    }
+
+    /// Si el SO del cliente puede usar el servicio; sin SO conocido no se puede comprobar y se permite
+    pub fn allows_platform(&self, os_name: Option<&str>) -> bool {
+        match os_name {
+            Some(os) if !self.allowed_platforms.is_empty() => {
+                self.allowed_platforms.iter().any(|p| p.eq_ignore_ascii_case(os))
+            }
+            _ => true,
+        }
+    }
+
+    /// Estado de un toggle del tenant, o `default` si no lo define
+    pub fn feature_enabled(&self, name: &str, default: bool) -> bool {
+        self.features.get(name).copied().unwrap_or(default)
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -18,3 +115,41 @@ pub trait BusinessRepository: Send + Sync {
     async fn find_by_id(&self, id: &str) -> Result<Business, BusinessRepoError>;
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_take_precedence_over_business_settings() {
+        let business = Business {
+            name: "Club".to_string(),
+            settings: TenantSettings {
+                brand_name: Some("Stored".to_string()),
+                logo_url: Some("https://cdn.example.com/logo.png".to_string()),
+                allowed_platforms: Some(vec!["iOS".to_string()]),
+                features: HashMap::from([("tracking".to_string(), true), ("badge".to_string(), false)]),
+                ..Default::default()
+            },
+        };
+        let overrides = TenantSettings {
+            brand_name: Some("Padel Club".to_string()),
+            primary_color: Some("#0A84FF".to_string()),
+            features: HashMap::from([("tracking".to_string(), false)]),
+            ..Default::default()
+        };
+        let defaults = TenantDefaults { brand_name: "Goil".to_string(), language: "ca".to_string() };
+
+        let tenant = TenantConfig::resolve("b1", Some(&business), Some(&overrides), &defaults);
+        assert_eq!(tenant.brand_name, "Padel Club");
+        assert_eq!(tenant.default_language, "ca");
+        assert_eq!(tenant.logo_url.as_deref(), Some("https://cdn.example.com/logo.png"));
+        assert_eq!(tenant.primary_color.as_deref(), Some("#0A84FF"));
+        assert!(!tenant.feature_enabled("tracking", true));
+        assert!(!tenant.feature_enabled("badge", true));
+        assert!(tenant.allows_platform(Some("ios")) && !tenant.allows_platform(Some("Android")));
+
+        let unknown = TenantConfig::resolve("b3", None, None, &defaults);
+        assert_eq!(unknown.brand_name, "Goil");
+        assert!(unknown.allows_platform(Some("Android")));
+    }
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha512};
+
+/// Flags consultados por los controllers (si el flag no existe se usa el valor por defecto de cada llamada)
+pub mod keys {
+    /// Sumar al badge las notificaciones no leídas de GetStream
+    pub const GETSTREAM_UNREAD_COUNT: &str = "getstream_unread_count";
+}
+
+/// Flag para activar un comportamiento de forma gradual
+///
+/// Está activo para un usuario si `enabled`, su business y su tipo de cuenta están incluidos
+/// (listas vacías = todos) y el usuario cae dentro de `percentage`. El reparto por porcentaje es estable:
+/// subir el porcentaje mantiene activos a los usuarios que ya lo tenían.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    pub business_ids: Vec<String>,
+    pub account_type_ids: Vec<String>,
+    /// Porcentaje de usuarios (0 - 100)
+    pub percentage: u8,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl FeatureFlag {
+    pub fn is_enabled_for(&self, business_id: &str, account_type_id: Option<&str>, user_id: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if !self.business_ids.is_empty() && !self.business_ids.iter().any(|id| id == business_id) {
+            return false;
+        }
+        if !self.account_type_ids.is_empty()
+            && !account_type_id.is_some_and(|account_type| self.account_type_ids.iter().any(|id| id == account_type))
+        {
+            return false;
+        }
+        self.percentage >= 100 || user_bucket(&self.key, user_id) < self.percentage
+    }
+}
+
+/// Posición estable del usuario (0 - 99) para el flag: cada flag reparte a los usuarios de forma distinta
+fn user_bucket(key: &str, user_id: &str) -> u8 {
+    let digest = Sha512::new().chain_update(key).chain_update(":").chain_update(user_id).finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(prefix) % 100) as u8
+}
+
+/// Cambio de un flag desde la API de administración
+#[derive(Clone, Debug)]
+pub struct FeatureFlagUpdate {
+    pub enabled: bool,
+    /// None = se mantiene el porcentaje actual (100 si el flag es nuevo)
+    pub percentage: Option<u8>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FeatureFlagRepoError {
+    #[error("unexpected error: {0}")]
+    Unexpected(String),
+}
+
+#[async_trait]
+pub trait FeatureFlagRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<FeatureFlag>, FeatureFlagRepoError>;
+    /// Actualiza el flag o lo crea (sin restricciones de business ni tipo de cuenta) si no existe
+    async fn upsert(&self, key: &str, update: &FeatureFlagUpdate) -> Result<FeatureFlag, FeatureFlagRepoError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(percentage: u8) -> FeatureFlag {
+        FeatureFlag {
+            key: "aggregated_badge".to_string(),
+            description: String::new(),
+            enabled: true,
+            business_ids: vec!["b1".to_string()],
+            account_type_ids: vec!["premium".to_string()],
+            percentage,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn evaluates_business_account_type_and_stable_percentage() {
+        let full = flag(100);
+        assert!(full.is_enabled_for("b1", Some("premium"), "u1"));
+        assert!(!full.is_enabled_for("b2", Some("premium"), "u1"));
+        assert!(!full.is_enabled_for("b1", None, "u1"));
+        assert!(!FeatureFlag { enabled: false, ..full.clone() }.is_enabled_for("b1", Some("premium"), "u1"));
+
+        let users: Vec<String> = (0..1000).map(|i| format!("user-{}", i)).collect();
+        let enabled_at = |percentage: u8| -> Vec<&String> {
+            users.iter().filter(|u| flag(percentage).is_enabled_for("b1", Some("premium"), u)).collect()
+        };
+        let (ten, fifty) = (enabled_at(10), enabled_at(50));
+        assert!((50..150).contains(&ten.len()), "{}", ten.len());
+        assert!((400..600).contains(&fifty.len()), "{}", fifty.len());
+        assert!(ten.iter().all(|u| fifty.contains(u)));
+        assert!(enabled_at(0).is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 pub mod notification;
-pub use notification::{Notification, NotificationRepository, NotificationRepoError};
+pub use notification::{Notification, NotificationEvent, NotificationRepository, NotificationRepoError};
 
 pub mod session;
 pub use session::{Session, SessionRepository, SessionRepoError};
@@ -13,7 +13,10 @@ pub mod analytics;
 pub use analytics::{NotificationReadRepository, NotificationReadRepoError};
 
 pub mod business;
-pub use business::{Business, BusinessRepository, BusinessRepoError};
+pub use business::{Business, BusinessRepository, BusinessRepoError, TenantConfig, TenantDefaults, TenantSettings};
 
 
 pub mod getstream;
+
+pub mod feature_flag;
+pub use feature_flag::{FeatureFlag, FeatureFlagRepository, FeatureFlagRepoError, FeatureFlagUpdate};
@@ -21,8 +21,39 @@ pub enum NotificationRepoError {
     Unexpected(String),
 }
 
+impl NotificationRepoError {
+    /// Tipo de error para los campos estructurados de los logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::Unexpected(_) => "unexpected",
+        }
+    }
+}
+
 #[async_trait]
 pub trait NotificationRepository: Send + Sync {
     async fn find_by_id(&self, id: &str, language: &str, business_id: &str) -> Result<Notification, NotificationRepoError>;
     async fn find_users_notifications(&self, users: &[SimplifiedUser], business_ids: &[String]) -> Result<Vec<String>, NotificationRepoError>;
 }
+
+/// Evento de engagement reportado por el cliente sobre una notificación
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotificationEvent {
+    Delivered,
+    Opened,
+    Clicked { target_url: String },
+    Dismissed,
+}
+
+impl NotificationEvent {
+    /// Nombre del evento tal como se expone en la API y en el tracking
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationEvent::Delivered => "delivered",
+            NotificationEvent::Opened => "opened",
+            NotificationEvent::Clicked { .. } => "clicked",
+            NotificationEvent::Dismissed => "dismissed",
+        }
+    }
+}
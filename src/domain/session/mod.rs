@@ -2,7 +2,8 @@ use async_trait::async_trait;
 
 #[derive(Clone, Debug)]
 pub struct Session {
-    pub language: String,
+    /// Idioma elegido en la app; sin él se usa el del tenant
+    pub language: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
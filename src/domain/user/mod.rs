@@ -17,6 +17,16 @@ pub enum UserRepoError {
     Unexpected(String),
 }
 
+impl UserRepoError {
+    /// Tipo de error para los campos estructurados de los logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::Unexpected(_) => "unexpected",
+        }
+    }
+}
+
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn find_simplified_by_id(&self, id: &str, business_id: &str) -> Result<SimplifiedUser, UserRepoError>;
@@ -6,6 +6,8 @@ use futures::stream::TryStreamExt; // Necesario para try_collect()
 
 use crate::domain::analytics::{NotificationReadRepository, NotificationReadRepoError};
 use crate::mappers::common::object_id_to_string_or_empty;
+use crate::infrastructure::indexes::RequiredIndex;
+use crate::metrics::observe_mongo;
 
 #[derive(Clone)]
 pub struct MongoNotificationReadRepository {
@@ -14,6 +16,11 @@ pub struct MongoNotificationReadRepository {
 
 impl MongoNotificationReadRepository {
     pub fn new(db: Database) -> Self { Self { db } }
+
+    /// Índices que necesitan las consultas (ver `IndexManager`)
+    pub fn required_indexes() -> Vec<RequiredIndex> {
+        vec![RequiredIndex::new("NotificationRead", doc! { "phone": 1, "businessId": 1 })]
+    }
 }
 
 #[async_trait]
@@ -22,7 +29,7 @@ impl NotificationReadRepository for MongoNotificationReadRepository {
         // Convertir businessIds de String a ObjectId
         let business_oids: Result<Vec<ObjectId>, _> = business_ids
             .iter()
-            .map(|bid| ObjectId::parse_str(bid))
+            .map(ObjectId::parse_str)
             .collect();
         let business_oids = business_oids.map_err(|e| NotificationReadRepoError::Unexpected(e.to_string()))?;
         
@@ -32,8 +39,7 @@ impl NotificationReadRepository for MongoNotificationReadRepository {
             "businessId": { "$in": business_oids }
         };
 
-        // IMPORTANTE: Para máximo rendimiento, crear índice en MongoDB:
-        // db.NotificationRead.createIndex({ "phone": 1, "businessId": 1 })
+        // Índice { phone: 1, businessId: 1 } declarado en `required_indexes`
         let options = FindOptions::builder()
             .projection(doc! { "notificationId": 1 })
             .limit(1000) // Límite razonable para obtener reads precisos
@@ -43,14 +49,9 @@ impl NotificationReadRepository for MongoNotificationReadRepository {
         let coll = self.db.collection::<Document>("NotificationRead");
         
         // Optimización: usar collect en lugar de iterar cursor para mejor rendimiento
-        let cursor = coll
-            .find(filter)
-            .with_options(options)
-            .await
-            .map_err(|e| NotificationReadRepoError::Unexpected(e.to_string()))?;
-        
-        let docs: Vec<Document> = cursor
-            .try_collect::<Vec<_>>()
+        let docs: Vec<Document> = observe_mongo("MongoNotificationReadRepository", "find_by_phone_and_business_ids", async {
+            coll.find(filter).with_options(options).await?.try_collect::<Vec<_>>().await
+        })
             .await
             .map_err(|e| NotificationReadRepoError::Unexpected(e.to_string()))?;
         
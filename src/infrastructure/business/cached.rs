@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::ConfigHandle;
+use crate::domain::{Business, BusinessRepository, BusinessRepoError};
+use crate::infrastructure::ttl_cache::TtlCache;
+
+/// Businesses cacheados como máximo; al llenarse se descartan los caducados o se vacía
+const MAX_ENTRIES: usize = 10_000;
+
+/// Decorador que cachea en memoria los businesses leídos de ClientDB
+///
+/// La configuración de un business cambia muy poco y se consulta en cada request (`session_guard`):
+/// se recuerda `tenants.cache_ttl_secs` (recargable en caliente; un cambio aplica a las lecturas nuevas).
+/// Los inexistentes también se cachean; los errores inesperados no, para reintentar en el siguiente request.
+#[derive(Clone)]
+pub struct CachedBusinessRepository<R: BusinessRepository> {
+    inner: R,
+    config: ConfigHandle,
+    /// Business leído (None si no existe)
+    entries: Arc<TtlCache<String, Option<Business>>>,
+}
+
+impl<R: BusinessRepository> CachedBusinessRepository<R> {
+    pub fn new(inner: R, config: ConfigHandle) -> Self {
+        Self { inner, config, entries: Arc::new(TtlCache::new(MAX_ENTRIES)) }
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.current().tenants.cache_ttl_secs)
+    }
+}
+
+#[async_trait]
+impl<R: BusinessRepository> BusinessRepository for CachedBusinessRepository<R> {
+    async fn find_by_id(&self, id: &str) -> Result<Business, BusinessRepoError> {
+        let ttl = self.ttl();
+        if ttl.is_zero() {
+            return self.inner.find_by_id(id).await;
+        }
+        if let Some(cached) = self.entries.get(id) {
+            return cached.ok_or(BusinessRepoError::NotFound);
+        }
+
+        match self.inner.find_by_id(id).await {
+            Ok(business) => {
+                self.entries.insert(id.to_string(), Some(business.clone()), ttl);
+                Ok(business)
+            }
+            Err(BusinessRepoError::NotFound) => {
+                self.entries.insert(id.to_string(), None, ttl);
+                Err(BusinessRepoError::NotFound)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -5,6 +5,7 @@ use mongodb::options::FindOneOptions;
 
 use crate::domain::{Business, BusinessRepository, BusinessRepoError};
 use crate::mappers::business::doc_to_domain;
+use crate::metrics::observe_mongo;
 
 #[derive(Clone)]
 pub struct MongoBusinessRepository {
@@ -23,13 +24,12 @@ impl BusinessRepository for MongoBusinessRepository {
         let oid = ObjectId::parse_str(id).map_err(|e| BusinessRepoError::Unexpected(e.to_string()))?;
         
         let options = FindOneOptions::builder()
-            .projection(doc! { "name": 1 })
+            .projection(doc! { "name": 1, "notificationsConfig": 1 })
             .build();
         
         let coll = self.db.collection::<Document>("Business");
-        let doc = match coll
-            .find_one(doc! { "_id": oid })
-            .with_options(options)
+        let query = coll.find_one(doc! { "_id": oid }).with_options(options);
+        let doc = match observe_mongo("MongoBusinessRepository", "find_by_id", query)
             .await
             .map_err(|e| BusinessRepoError::Unexpected(e.to_string()))? {
             Some(d) => d,
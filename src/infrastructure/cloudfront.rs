@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rsa::RsaPrivateKey;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use sha1::Sha1;
+use std::sync::Arc;
+
+use crate::config::{CloudFrontConfig, UrlCacheConfig};
+use crate::infrastructure::signed_url_cache::SignedUrlCache;
+use crate::infrastructure::storage::{is_absolute_url, ImageUrlSigner, StorageObject};
+
+/// Servicio para firmar URLs de CloudFront con canned policy
+/// (equivalente a getSignedUrl de @aws-sdk/cloudfront-signer)
+#[derive(Clone)]
+pub struct CloudFrontUrlSigner {
+    domain: String,
+    key_pair_id: String,
+    signing_key: Arc<SigningKey<Sha1>>,
+    cache: Arc<SignedUrlCache>,
+}
+
+impl CloudFrontUrlSigner {
+    /// Crea el firmador a partir del dominio de la distribución, el key pair ID y la clave privada RSA (PEM)
+    pub fn new(domain: &str, key_pair_id: &str, private_key_pem: &str, cache: &UrlCacheConfig) -> Result<Self, String> {
+        let private_key = RsaPrivateKey::from_pkcs1_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs8_pem(private_key_pem))
+            .map_err(|e| format!("Invalid CloudFront private key: {}", e))?;
+
+        let domain = domain.trim_end_matches('/');
+        let domain = if domain.starts_with("http://") || domain.starts_with("https://") {
+            domain.to_string()
+        } else {
+            format!("https://{}", domain)
+        };
+
+        Ok(Self {
+            domain,
+            key_pair_id: key_pair_id.to_string(),
+            signing_key: Arc::new(SigningKey::<Sha1>::new(private_key)),
+            cache: Arc::new(SignedUrlCache::from_config(cache)),
+        })
+    }
+
+    /// Crea el firmador desde la sección `[cloudfront]`
+    /// La clave se toma de `private_key` (admite "\n" escapados) o, si está vacía, de `private_key_path`
+    pub fn from_config(config: &CloudFrontConfig, cache: &UrlCacheConfig) -> Result<Self, String> {
+        let private_key = match &config.private_key_path {
+            Some(path) if config.private_key.is_empty() => std::fs::read_to_string(path)
+                .map_err(|e| format!("Error reading CloudFront private key '{}': {}", path.display(), e))?,
+            _ => config.private_key.expose().replace("\\n", "\n"),
+        };
+
+        Self::new(&config.domain, &config.key_pair_id, &private_key, cache)
+    }
+
+    /// Firma la URL de CloudFront de un objeto con una validez de `expires_in` segundos
+    /// La firma es local (RSA), no requiere llamadas de red
+    /// La distribución tiene un único origen, así que el bucket del objeto se ignora
+    fn sign(&self, key: &str, expires_in: u64) -> Result<String, String> {
+        if is_absolute_url(key) {
+            return Ok(key.to_string());
+        }
+
+        if let Some(url) = self.cache.get(key, expires_in) {
+            return Ok(url);
+        }
+
+        let resource = format!("{}/{}", self.domain, key.trim_start_matches('/'));
+        let expires_at = chrono::Utc::now().timestamp() + expires_in as i64;
+        let url = self.sign_resource(&resource, expires_at);
+        self.cache.insert(key, expires_in, url.clone());
+
+        Ok(url)
+    }
+
+    fn sign_resource(&self, resource: &str, expires_at: i64) -> String {
+        // Canned policy: CloudFront exige el JSON exacto, sin espacios
+        let policy = format!(
+            r#"{{"Statement":[{{"Resource":"{}","Condition":{{"DateLessThan":{{"AWS:EpochTime":{}}}}}}}]}}"#,
+            resource, expires_at
+        );
+        let signature = self.signing_key.sign(policy.as_bytes());
+        let separator = if resource.contains('?') { '&' } else { '?' };
+
+        format!(
+            "{}{}Expires={}&Signature={}&Key-Pair-Id={}",
+            resource,
+            separator,
+            expires_at,
+            cloudfront_base64(&signature.to_bytes()),
+            self.key_pair_id
+        )
+    }
+}
+
+#[async_trait]
+impl ImageUrlSigner for CloudFrontUrlSigner {
+    async fn sign_url(&self, object: &StorageObject, expires_in: u64) -> Result<String, String> {
+        self.sign(&object.key, expires_in)
+    }
+
+    async fn sign_urls(&self, objects: &[StorageObject], expires_in: u64) -> Result<Vec<String>, String> {
+        objects.iter().map(|object| self.sign(&object.key, expires_in)).collect()
+    }
+}
+
+/// Base64 "URL safe" propio de CloudFront: '+' -> '-', '=' -> '_', '/' -> '~'
+fn cloudfront_base64(bytes: &[u8]) -> String {
+    BASE64
+        .encode(bytes)
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '=' => '_',
+            '/' => '~',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs1::EncodeRsaPrivateKey;
+    use rsa::pkcs1v15::{Signature, VerifyingKey};
+    use rsa::signature::Verifier;
+
+    #[test]
+    fn signs_canned_policy_url() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let pem = private_key.to_pkcs1_pem(rsa::pkcs1::LineEnding::LF).unwrap();
+        let signer = CloudFrontUrlSigner::new("d111111abcdef8.cloudfront.net/", "K2JCJMDEHXQW5F", &pem, &UrlCacheConfig::default())
+            .unwrap();
+
+        let url = signer.sign_resource("https://d111111abcdef8.cloudfront.net/notifications/images/a.png", 1700000000);
+        let (resource, query) = url.split_once('?').unwrap();
+        assert_eq!(resource, "https://d111111abcdef8.cloudfront.net/notifications/images/a.png");
+        assert!(query.starts_with("Expires=1700000000&Signature="));
+        assert!(query.ends_with("&Key-Pair-Id=K2JCJMDEHXQW5F"));
+
+        // La firma debe verificar contra la policy con la clave pública
+        let encoded = query.split("Signature=").nth(1).unwrap().split('&').next().unwrap();
+        assert!(!encoded.contains(['+', '=', '/']));
+        let standard: String = encoded.chars().map(|c| match c { '-' => '+', '_' => '=', '~' => '/', c => c }).collect();
+        let signature = Signature::try_from(BASE64.decode(standard).unwrap().as_slice()).unwrap();
+        let policy = r#"{"Statement":[{"Resource":"https://d111111abcdef8.cloudfront.net/notifications/images/a.png","Condition":{"DateLessThan":{"AWS:EpochTime":1700000000}}}]}"#;
+        let verifying_key = VerifyingKey::<Sha1>::new(private_key.to_public_key());
+        assert!(verifying_key.verify(policy.as_bytes(), &signature).is_ok());
+    }
+}
@@ -1,7 +1,11 @@
 use mongodb::{Client, Database};
 use mongodb::options::{ClientOptions, ServerApi, ServerApiVersion};
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::config::MongoConfig;
+use crate::infrastructure::mongo_monitor::MongoMonitor;
+
 /// Estructura que contiene todas las bases de datos de MongoDB
 #[derive(Clone)]
 pub struct Databases {
@@ -15,23 +19,15 @@ impl Databases {
     /// Inicializa el cliente MongoDB y crea las conexiones a las bases de datos necesarias
     /// 
     /// # Parámetros
+    /// - `config`: URI y nombres de las bases de datos (`AppConfig::mongodb`)
     /// - `max_pool_size`: Número máximo de conexiones en el pool por worker (opcional)
     /// - `min_pool_size`: Número mínimo de conexiones en el pool (opcional)
     pub async fn init_with_pool_config(
+        config: &MongoConfig,
         max_pool_size: Option<u32>,
         min_pool_size: Option<u32>,
     ) -> mongodb::error::Result<Self> {
-        let uri = std::env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
-        let notifications_db_name = std::env::var("MONGODB_NOTIFICATIONS_DB")
-            .unwrap_or_else(|_| "NotificationDB".to_string());
-        let account_db_name = std::env::var("MONGODB_ACCOUNT_DB")
-            .unwrap_or_else(|_| "AccountDB".to_string());
-        let analytics_db_name = std::env::var("MONGODB_ANALYTICS_DB")
-            .unwrap_or_else(|_| "AnalyticsDB".to_string());
-        let client_db_name = std::env::var("MONGODB_CLIENT_DB")
-            .unwrap_or_else(|_| "ClientDB".to_string());
-        
-        let parsed = ClientOptions::parse(&uri).await?;
+        let parsed = ClientOptions::parse(config.uri.expose()).await?;
         let mut opts = parsed;
         opts.app_name = Some("server-notifications".to_string());
         // Timeouts optimizados para alto rendimiento y estabilidad
@@ -48,21 +44,26 @@ impl Databases {
         opts.heartbeat_freq = Some(Duration::from_secs(10)); // Heartbeat cada 10 segundos para mantener conexiones activas
         opts.server_api = Some(ServerApi::builder().version(ServerApiVersion::V1).build());
 
+        // Latencia por colección, comandos lentos (`slow_query_ms`) y espera del pool
+        let monitor = Arc::new(MongoMonitor::new(Duration::from_millis(config.slow_query_ms)));
+        opts.command_event_handler = Some(monitor.command_handler());
+        opts.cmap_event_handler = Some(MongoMonitor::cmap_handler());
+
         let client = Client::with_options(opts)?;
         
         Ok(Self {
-            notifications_db: client.database(&notifications_db_name),
-            account_db: client.database(&account_db_name),
-            analytics_db: client.database(&analytics_db_name),
-            client_db: client.database(&client_db_name),
+            notifications_db: client.database(&config.notifications_db),
+            account_db: client.database(&config.account_db),
+            analytics_db: client.database(&config.analytics_db),
+            client_db: client.database(&config.client_db),
         })
     }
 
     /// Inicializa el cliente MongoDB con configuración por defecto
     /// Para configuración personalizada, usar `init_with_pool_config`
     #[allow(dead_code)] // Mantenido para compatibilidad futura
-    pub async fn init() -> mongodb::error::Result<Self> {
-        Self::init_with_pool_config(None, None).await
+    pub async fn init(config: &MongoConfig) -> mongodb::error::Result<Self> {
+        Self::init_with_pool_config(config, None, None).await
     }
 }
 
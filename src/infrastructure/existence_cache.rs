@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use crate::config::ExistenceCacheConfig;
+use crate::infrastructure::storage::StorageObject;
+use crate::infrastructure::ttl_cache::TtlCache;
+
+/// Cache en memoria del resultado de comprobar si un objeto existe (HeadObject)
+///
+/// Los objetos existentes se recuerdan `positive_ttl` y los inexistentes `negative_ttl`: una subida
+/// corregida aparece en poco tiempo sin que cada request con una imagen rota vuelva a lanzar un HEAD.
+pub struct ExistenceCache {
+    entries: TtlCache<StorageObject, bool>,
+    positive_ttl: Duration,
+    negative_ttl: Duration,
+}
+
+impl ExistenceCache {
+    pub fn new(positive_ttl: Duration, negative_ttl: Duration, max_entries: usize) -> Self {
+        Self { entries: TtlCache::new(max_entries), positive_ttl, negative_ttl }
+    }
+
+    /// Crea la caché con `storage.existence_cache`
+    pub fn from_config(config: &ExistenceCacheConfig) -> Self {
+        Self::new(
+            Duration::from_secs(config.ttl_secs),
+            Duration::from_secs(config.negative_ttl_secs),
+            config.max_entries,
+        )
+    }
+
+    /// Resultado cacheado si no ha caducado
+    pub fn get(&self, object: &StorageObject) -> Option<bool> {
+        self.entries.get(object)
+    }
+
+    pub fn insert(&self, object: &StorageObject, exists: bool) {
+        self.insert_at(object, exists, Instant::now())
+    }
+
+    fn insert_at(&self, object: &StorageObject, exists: bool, now: Instant) {
+        let ttl = if exists { self.positive_ttl } else { self.negative_ttl };
+        self.entries.insert_at(object.clone(), exists, ttl, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(key: &str) -> StorageObject {
+        StorageObject { key: key.to_string(), bucket: None }
+    }
+
+    #[test]
+    fn negative_results_expire_before_positive_ones() {
+        let cache = ExistenceCache::new(Duration::from_secs(300), Duration::from_secs(60), 10);
+        let now = Instant::now();
+        cache.insert_at(&object("ok.png"), true, now);
+        cache.insert_at(&object("missing.png"), false, now);
+
+        let later = now + Duration::from_secs(120);
+        assert_eq!(cache.entries.get_at(&object("ok.png"), later), Some(true));
+        assert_eq!(cache.entries.get_at(&object("missing.png"), later), None);
+        assert_eq!(cache.entries.get_at(&object("missing.png"), now + Duration::from_secs(30)), Some(false));
+    }
+
+    #[test]
+    fn distinguishes_buckets() {
+        let cache = ExistenceCache::new(Duration::from_secs(300), Duration::from_secs(60), 10);
+        cache.insert(&object("a.png"), true);
+        let other_bucket = StorageObject { key: "a.png".to_string(), bucket: Some("club".to_string()) };
+        assert_eq!(cache.get(&other_bucket), None);
+    }
+}
@@ -2,33 +2,57 @@ use async_trait::async_trait;
 
 use crate::domain::Notification;
 use serde_json::Value;
+use crate::config::ConfigHandle;
 use crate::domain::getstream::{GetStreamRepository, GetStreamRepoError};
 use crate::infrastructure::external::getstream_auth::generate_getstream_jwt;
+use crate::metrics::record_external_call;
+use crate::telemetry::propagate_headers;
 
-#[derive(Clone, Default)]
-pub struct HttpGetStreamRepository;
+#[derive(Clone)]
+pub struct HttpGetStreamRepository {
+    config: ConfigHandle,
+}
+
+impl HttpGetStreamRepository {
+    pub fn new(config: ConfigHandle) -> Self {
+        Self { config }
+    }
+
+    /// API key y JWT firmado con el secreto en uso; error si GetStream no está configurado
+    fn credentials(&self, user_id: Option<&str>) -> Result<(String, String), GetStreamRepoError> {
+        let config = self.config.current();
+        let getstream = &config.getstream;
+        if getstream.api_key.is_empty() || getstream.secret.is_empty() {
+            return Err(GetStreamRepoError::Unexpected(
+                "GetStream is not configured (GETSTREAM_API_KEY / GETSTREAM_SECRET)".to_string(),
+            ));
+        }
+        let token = generate_getstream_jwt(getstream.secret.expose(), user_id, 60)?;
+        Ok((getstream.api_key.clone(), token))
+    }
+}
 
 // Nota: las claims ahora viven en getstream_auth.rs
 
 #[async_trait]
 impl GetStreamRepository for HttpGetStreamRepository {
+    #[tracing::instrument(name = "getstream.find_message", skip_all, fields(otel.kind = "client"))]
     async fn find_message_by_uuid(&self, id: &str, _user_id: &str, _language: &str, _business_id: &str) -> Result<Notification, GetStreamRepoError> {
         // 1) Generar JWT
-        let token = generate_getstream_jwt(None, 60)?;
+        let (api_key, token) = self.credentials(None)?;
 
         // 3) Preparar request HTTP
-        let api_key = std::env::var("GETSTREAM_API_KEY").map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
         let url = format!("https://chat.stream-io-api.com/messages/{}", id);
         let client = reqwest::Client::new();
 
-        let resp = client
-            .get(&url)
+        let resp = propagate_headers(client.get(&url))
             .header("Stream-Auth-Type", "jwt")
             .header("Authorization", token)
             .header("api_key", api_key)
             .send()
-            .await
-            .map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
+            .await;
+        record_external_call("getstream", "find_message", resp.as_ref().is_ok_and(|r| r.status().is_success()));
+        let resp = resp.map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
 
         let status = resp.status();
         let body = resp.text().await.map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
@@ -90,20 +114,20 @@ impl GetStreamRepository for HttpGetStreamRepository {
         })
     }
 
+    #[tracing::instrument(name = "getstream.get_unread_count", skip_all, fields(otel.kind = "client"))]
     async fn get_unread_count(&self, user_id: &str) -> Result<i32, GetStreamRepoError> {
-        let token = generate_getstream_jwt(Some(user_id), 60)?;
+        let (api_key, token) = self.credentials(Some(user_id))?;
 
-        let api_key = std::env::var("GETSTREAM_API_KEY").map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
         let url = "https://chat.stream-io-api.com/unread";
         let client = reqwest::Client::new();
-        let resp = client
-            .get(url)
+        let resp = propagate_headers(client.get(url))
             .header("Stream-Auth-Type", "jwt")
             .header("Authorization", token)
             .header("api_key", api_key)
             .send()
-            .await
-            .map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
+            .await;
+        record_external_call("getstream", "get_unread_count", resp.as_ref().is_ok_and(|r| r.status().is_success()));
+        let resp = resp.map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
 
         let status = resp.status();
         let body = resp.text().await.map_err(|e| GetStreamRepoError::Unexpected(e.to_string()))?;
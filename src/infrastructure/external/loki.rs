@@ -0,0 +1,314 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::config::{LokiCompression, LokiConfig};
+
+/// Target de los eventos de tracing del propio shipper; `LogSinks` no los envía a Loki para no
+/// realimentar un Loki caído con sus propios errores (siguen llegando a stdout)
+pub const LOG_TARGET: &str = module_path!();
+
+/// Líneas de log descartadas antes de llegar a Loki (buffer lleno o push fallido tras los reintentos)
+pub static LOKI_DROPPED_ENTRIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "loki_dropped_entries_total",
+        "Log entries dropped before reaching Loki",
+        &["reason"]
+    )
+    .expect("metric registered twice")
+});
+
+/// Asegura que la URL termine en /api/v1/push
+fn push_url(loki_url: &str) -> String {
+    let loki_url = loki_url.trim_end_matches('/');
+    if loki_url.ends_with("/api/v1/push") {
+        loki_url.to_string()
+    } else if loki_url.ends_with("/loki") {
+        format!("{}/api/v1/push", loki_url)
+    } else {
+        format!("{}/loki/api/v1/push", loki_url)
+    }
+}
+
+/// Buffer acotado de líneas de log (timestamp en ns, línea)
+struct LogBuffer {
+    entries: VecDeque<(i64, String)>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self { entries: VecDeque::new(), capacity }
+    }
+
+    /// Añade una entrada; devuelve cuántas de las más antiguas se descartaron para hacerle sitio
+    fn push(&mut self, timestamp_ns: i64, line: String) -> usize {
+        let mut dropped = 0;
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+            dropped += 1;
+        }
+        self.entries.push_back((timestamp_ns, line));
+        dropped
+    }
+
+    fn drain(&mut self, max: usize) -> Vec<(i64, String)> {
+        let n = max.min(self.entries.len());
+        self.entries.drain(..n).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Envía los logs a Loki por lotes desde una única tarea en background
+///
+/// `push` solo encola en memoria (no bloquea el request ni lanza tareas); la tarea envía un único
+/// payload `streams` cuando se llena un lote o cada `flush_interval`, comprimido con gzip y con
+/// reintentos con backoff exponencial. Las entradas descartadas se cuentan en `loki_dropped_entries_total`.
+#[derive(Clone)]
+pub struct LokiShipper {
+    buffer: Arc<Mutex<LogBuffer>>,
+    notify: Arc<Notify>,
+    batch_size: usize,
+    /// Se activa en el apagado: la tarea envía todo lo pendiente y termina
+    closing: Arc<AtomicBool>,
+    worker: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl LokiShipper {
+    /// Crea el shipper y arranca la tarea de envío (requiere un runtime de tokio)
+    /// `config` es la sección `[logging.loki]` (validada al cargarla); `labels` son las del stream (job, service, host)
+    pub fn start(config: LokiConfig, labels: Vec<(String, String)>) -> Self {
+        let shipper = Self {
+            buffer: Arc::new(Mutex::new(LogBuffer::new(config.max_buffer))),
+            notify: Arc::new(Notify::new()),
+            batch_size: config.batch_size,
+            closing: Arc::new(AtomicBool::new(false)),
+            worker: Arc::new(Mutex::new(None)),
+        };
+
+        let worker = shipper.clone();
+        let handle = tokio::spawn(async move { worker.run(config, labels).await });
+        if let Ok(mut slot) = shipper.worker.lock() {
+            *slot = Some(handle);
+        }
+
+        shipper
+    }
+
+    /// Shipper sin tarea de envío, para inspeccionar en tests lo que se enviaría
+    #[cfg(test)]
+    pub fn unstarted(max_buffer: usize) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(LogBuffer::new(max_buffer))),
+            notify: Arc::new(Notify::new()),
+            batch_size: max_buffer,
+            closing: Arc::new(AtomicBool::new(false)),
+            worker: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Líneas encoladas pendientes de envío
+    #[cfg(test)]
+    pub fn pending_lines(&self) -> Vec<String> {
+        self.take_batch().into_iter().map(|(_, line)| line).collect()
+    }
+
+    /// Encola una línea de log con el timestamp actual
+    pub fn push(&self, line: String) {
+        let timestamp_ns = chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_else(|| chrono::Utc::now().timestamp() * 1_000_000_000);
+
+        let (dropped, len) = match self.buffer.lock() {
+            Ok(mut buffer) => (buffer.push(timestamp_ns, line), buffer.len()),
+            Err(_) => return,
+        };
+
+        if dropped > 0 {
+            LOKI_DROPPED_ENTRIES.with_label_values(&["buffer_full"]).inc_by(dropped as u64);
+        }
+        if len >= self.batch_size {
+            self.notify.notify_one();
+        }
+    }
+
+    /// Envía todo lo pendiente y detiene la tarea, esperando como máximo `timeout`
+    /// Devuelve false si no terminó a tiempo (las entradas restantes se pierden)
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.closing.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+
+        let handle = self.worker.lock().ok().and_then(|mut slot| slot.take());
+        match handle {
+            Some(handle) => tokio::time::timeout(timeout, handle).await.is_ok(),
+            None => true,
+        }
+    }
+
+    fn take_batch(&self) -> Vec<(i64, String)> {
+        self.buffer
+            .lock()
+            .map(|mut buffer| buffer.drain(self.batch_size))
+            .unwrap_or_default()
+    }
+
+    async fn run(self, config: LokiConfig, labels: Vec<(String, String)>) {
+        let push_url = push_url(&config.url);
+        let flush_interval = Duration::from_millis(config.flush_interval_ms);
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client for Loki");
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(flush_interval) => {}
+                _ = self.notify.notified() => {}
+            }
+            let closing = self.closing.load(Ordering::SeqCst);
+
+            // Vaciar todo lo acumulado en lotes de `batch_size`
+            loop {
+                let batch = self.take_batch();
+                if batch.is_empty() {
+                    break;
+                }
+                let full = batch.len() >= self.batch_size;
+                if let Err(e) = send_batch(&client, &config, &push_url, &labels, &batch).await {
+                    tracing::error!(entries = batch.len(), error = %e, "Dropping Loki log batch");
+                    LOKI_DROPPED_ENTRIES.with_label_values(&["push_failed"]).inc_by(batch.len() as u64);
+                }
+                if !full {
+                    break;
+                }
+            }
+
+            if closing {
+                return;
+            }
+        }
+    }
+}
+
+/// Payload de push de Loki con un único stream
+fn build_payload(labels: &[(String, String)], entries: &[(i64, String)]) -> Value {
+    let stream: serde_json::Map<String, Value> = labels
+        .iter()
+        .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+        .collect();
+    let values: Vec<Value> = entries
+        .iter()
+        .map(|(ts, line)| json!([ts.to_string(), line]))
+        .collect();
+
+    json!({ "streams": [{ "stream": stream, "values": values }] })
+}
+
+fn gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// Envía un lote, reintentando errores de red, 429 y 5xx con backoff exponencial (200ms, 400ms, ...)
+async fn send_batch(
+    client: &Client,
+    config: &LokiConfig,
+    push_url: &str,
+    labels: &[(String, String)],
+    entries: &[(i64, String)],
+) -> Result<(), String> {
+    let gzip_enabled = config.compression == LokiCompression::Gzip;
+    let body = serde_json::to_vec(&build_payload(labels, entries))
+        .map_err(|e| format!("Error serializing Loki payload: {}", e))?;
+    let body = if gzip_enabled {
+        gzip(&body).map_err(|e| format!("Error compressing Loki payload: {}", e))?
+    } else {
+        body
+    };
+
+    let mut attempt = 0;
+    loop {
+        let mut request = client
+            .post(push_url)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+        if gzip_enabled {
+            request = request.header("Content-Encoding", "gzip");
+        }
+
+        let error = match request.send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(()),
+            Ok(resp) => {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                // Los 4xx (salvo 429) no se arreglan reintentando
+                if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+                    return Err(format!("Loki responded {}: {}", status, text));
+                }
+                format!("Loki responded {}: {}", status, text)
+            }
+            Err(e) => format!("Error sending logs to Loki: {}", e),
+        };
+
+        if attempt >= config.max_retries {
+            return Err(error);
+        }
+        tokio::time::sleep(retry_delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Espera exponencial antes del reintento `attempt` (200 ms, 400 ms, 800 ms...), limitada a `MAX_RETRY_DELAY`
+fn retry_delay(attempt: u32) -> Duration {
+    const BASE_DELAY_MS: u64 = 200;
+    const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+    let factor = 2u64.saturating_pow(attempt.min(16));
+    Duration::from_millis(BASE_DELAY_MS.saturating_mul(factor)).min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_oldest_entries_when_full() {
+        let mut buffer = LogBuffer::new(2);
+        assert_eq!(buffer.push(1, "a".to_string()), 0);
+        assert_eq!(buffer.push(2, "b".to_string()), 0);
+        assert_eq!(buffer.push(3, "c".to_string()), 1);
+        assert_eq!(buffer.drain(10), vec![(2, "b".to_string()), (3, "c".to_string())]);
+    }
+
+    #[test]
+    fn builds_single_stream_payload() {
+        let labels = vec![("job".to_string(), "server-notifications".to_string())];
+        let payload = build_payload(&labels, &[(1, "a".to_string()), (2, "b".to_string())]);
+        assert_eq!(
+            payload,
+            json!({ "streams": [{ "stream": { "job": "server-notifications" }, "values": [["1", "a"], ["2", "b"]] }] })
+        );
+        assert_eq!(push_url("https://gobs.goil.app/loki/loki"), "https://gobs.goil.app/loki/loki/api/v1/push");
+        assert_eq!(push_url("http://localhost:3100"), "http://localhost:3100/loki/api/v1/push");
+    }
+
+    #[test]
+    fn retry_delay_is_exponential_and_capped() {
+        assert_eq!(retry_delay(0), Duration::from_millis(200));
+        assert_eq!(retry_delay(3), Duration::from_millis(1600));
+        assert_eq!(retry_delay(6), Duration::from_secs(10));
+        assert_eq!(retry_delay(u32::MAX), Duration::from_secs(10));
+    }
+}
@@ -2,3 +2,4 @@ pub mod getstream;
 pub mod getstream_auth;
 pub mod queue;
 
+pub mod loki;
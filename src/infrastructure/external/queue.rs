@@ -2,36 +2,37 @@ use serde::Serialize;
 use std::sync::Arc;
 use reqwest::Client;
 
+use crate::config::ConfigHandle;
+use crate::domain::NotificationEvent;
+use crate::types::DeviceInfo;
+
+/// Cliente de la API de la cola; la URL y el timeout se leen de la configuración en cada envío (recargables)
 #[derive(Debug, Clone)]
 pub struct QueueService {
     client: Arc<Client>,
-    queue_url: String,
+    config: ConfigHandle,
 }
 
 impl QueueService {
-    pub fn new() -> Self {
+    pub fn new(config: ConfigHandle) -> Self {
         let client = Arc::new(
             Client::builder()
-                .timeout(std::time::Duration::from_secs(5))
                 .build()
                 .expect("Failed to create HTTP client for queue service")
         );
         
-        let queue_url = std::env::var("QUEUE_URL")
-            .unwrap_or_else(|_| "https://community.goil.app/api/v2/queue".to_string());
-        
         Self {
             client,
-            queue_url,
+            config,
         }
     }
 }
 
 #[derive(Serialize)]
 #[allow(dead_code)] // Se usa cuando el tracking está activo
-struct QueuePayload {
+struct QueuePayload<P: Serialize> {
     name: String,
-    params: TrackNotificationParams,
+    params: P,
 }
 
 #[derive(Serialize)]
@@ -49,9 +50,26 @@ pub struct TrackNotificationParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub device_client_os: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_client_os_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
 }
 
+/// Parámetros de un evento de engagement (delivered, opened, clicked, dismissed)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackNotificationEventParams {
+    #[serde(flatten)]
+    pub track: TrackNotificationParams,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub occurred_at: Option<i64>,
+}
+
 #[derive(Debug)]
 pub struct QueueRequestHeaders {
     pub authorization: Option<String>,
@@ -61,19 +79,75 @@ pub struct QueueRequestHeaders {
     pub x_client_id: Option<String>,
 }
 
+/// Nombre del job de la cola para cada tipo de evento de engagement
+pub fn notification_event_job_name(event: &NotificationEvent) -> &'static str {
+    match event {
+        NotificationEvent::Delivered => "TRACK_NOTIFICATION_DELIVERED",
+        NotificationEvent::Opened => "TRACK_NOTIFICATION_OPENED",
+        NotificationEvent::Clicked { .. } => "TRACK_NOTIFICATION_CLICKED",
+        NotificationEvent::Dismissed => "TRACK_NOTIFICATION_DISMISSED",
+    }
+}
+
+impl TrackNotificationParams {
+    /// Construye los parámetros de tracking con la información del dispositivo ya parseada
+    pub fn new(
+        id: &str,
+        business_id: Option<String>,
+        account_id: Option<String>,
+        session_id: Option<String>,
+        device: &DeviceInfo,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            business_id,
+            account_id,
+            device_client_type: device.device_type.map(|t| t.as_str().to_string()),
+            device_client_model: device.model.clone(),
+            device_client_os: device.os_name.clone(),
+            device_client_os_version: device.os_version.clone(),
+            app_version: device.app_version.clone(),
+            session_id,
+        }
+    }
+}
+
 impl QueueService {
     pub async fn enqueue_track_notification(
         &self,
         params: TrackNotificationParams,
         headers: QueueRequestHeaders,
+    ) -> Result<(), String> {
+        self.enqueue("TRACK_NOTIFICATION", params, headers).await
+    }
+
+    /// Encola un evento de engagement con el job específico del tipo de evento
+    pub async fn enqueue_notification_event(
+        &self,
+        event: &NotificationEvent,
+        params: TrackNotificationEventParams,
+        headers: QueueRequestHeaders,
+    ) -> Result<(), String> {
+        self.enqueue(notification_event_job_name(event), params, headers).await
+    }
+
+    #[tracing::instrument(name = "queue.enqueue", skip_all, fields(otel.kind = "client", job = name))]
+    async fn enqueue<P: Serialize>(
+        &self,
+        name: &str,
+        params: P,
+        headers: QueueRequestHeaders,
     ) -> Result<(), String> {
         let payload = QueuePayload {
-            name: "TRACK_NOTIFICATION".to_string(),
+            name: name.to_string(),
             params,
         };
 
-        let mut request = self.client
-            .post(&self.queue_url)
+        let queue = self.config.current().queue.clone();
+
+        // traceparent y x-request-id: la cola continúa la traza y la correlación del request original
+        let mut request = crate::telemetry::propagate_headers(self.client.post(&queue.url))
+            .timeout(std::time::Duration::from_millis(queue.timeout_ms))
             .json(&payload);
 
         // Añadir headers del request original
@@ -93,7 +167,10 @@ impl QueueService {
             request = request.header("x-client-id", client_id);
         }
 
-        match request.send().await {
+        let response = request.send().await;
+        crate::metrics::record_external_call("queue", name, response.as_ref().is_ok_and(|r| r.status().is_success()));
+
+        match response {
             Ok(response) => {
                 if response.status().is_success() {
                     Ok(())
@@ -108,3 +185,57 @@ impl QueueService {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_jobs_flatten_track_fields() {
+        let event = NotificationEvent::Clicked { target_url: "https://goil.app/club".to_string() };
+        assert_eq!(notification_event_job_name(&event), "TRACK_NOTIFICATION_CLICKED");
+        assert_eq!(notification_event_job_name(&NotificationEvent::Delivered), "TRACK_NOTIFICATION_DELIVERED");
+        assert_eq!(notification_event_job_name(&NotificationEvent::Opened), "TRACK_NOTIFICATION_OPENED");
+        assert_eq!(notification_event_job_name(&NotificationEvent::Dismissed), "TRACK_NOTIFICATION_DISMISSED");
+
+        let params = TrackNotificationEventParams {
+            track: TrackNotificationParams::new("n1", Some("b1".to_string()), None, None, &DeviceInfo::default()),
+            event: event.name().to_string(),
+            target_url: Some("https://goil.app/club".to_string()),
+            occurred_at: Some(1700000000000),
+        };
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            serde_json::json!({
+                "id": "n1",
+                "businessId": "b1",
+                "event": "clicked",
+                "targetUrl": "https://goil.app/club",
+                "occurredAt": 1700000000000i64
+            })
+        );
+    }
+
+    #[test]
+    fn android_app_headers_keep_the_device_type() {
+        use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("user-agent", "okhttp/4.12.0"),
+            ("x-client-device", "SM-S911B"),
+            ("x-client-os", "Android 14"),
+            ("x-app-version", "3.5.0"),
+        ] {
+            headers.insert(HeaderName::from_static(name), HeaderValue::from_static(value));
+        }
+        let params = TrackNotificationParams::new("n1", None, None, None, &DeviceInfo::from_headers(&headers));
+        assert_eq!(params.device_client_type.as_deref(), Some("phone"));
+        assert_eq!(params.device_client_model.as_deref(), Some("SM-S911B"));
+        assert_eq!(params.device_client_os.as_deref(), Some("Android"));
+
+        headers.insert(HeaderName::from_static("x-client-device-type"), HeaderValue::from_static("tablet"));
+        let params = TrackNotificationParams::new("n1", None, None, None, &DeviceInfo::from_headers(&headers));
+        assert_eq!(params.device_client_type.as_deref(), Some("tablet"));
+    }
+}
@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::config::ConfigHandle;
+use crate::domain::{FeatureFlag, FeatureFlagRepository, FeatureFlagRepoError, FeatureFlagUpdate};
+
+/// Flags leídos y momento de la lectura
+type CachedFlags = (Arc<Vec<FeatureFlag>>, Instant);
+
+/// Decorador que cachea en memoria la lista completa de flags
+///
+/// Los flags se evalúan en cada request: se leen de Mongo como mucho una vez cada `feature_flags.cache_ttl_secs`
+/// (recargable en caliente). Un cambio desde la API admin invalida la caché de esta instancia; el resto de
+/// instancias lo ven al caducar la suya. Si Mongo falla se siguen usando los flags anteriores.
+#[derive(Clone)]
+pub struct CachedFeatureFlagRepository<R: FeatureFlagRepository> {
+    inner: R,
+    config: ConfigHandle,
+    flags: Arc<RwLock<Option<CachedFlags>>>,
+}
+
+impl<R: FeatureFlagRepository> CachedFeatureFlagRepository<R> {
+    pub fn new(inner: R, config: ConfigHandle) -> Self {
+        Self { inner, config, flags: Arc::new(RwLock::new(None)) }
+    }
+
+    /// Flags cacheados (aunque hayan caducado) y si siguen vigentes
+    fn cached(&self, ttl: Duration) -> Option<(Arc<Vec<FeatureFlag>>, bool)> {
+        let flags = self.flags.read().ok()?;
+        let (flags, loaded_at) = flags.as_ref()?;
+        Some((flags.clone(), loaded_at.elapsed() < ttl))
+    }
+
+    fn store(&self, flags: Option<Arc<Vec<FeatureFlag>>>) {
+        if let Ok(mut cached) = self.flags.write() {
+            *cached = flags.map(|flags| (flags, Instant::now()));
+        }
+    }
+
+    /// Flags vigentes: los cacheados o, si han caducado, los de Mongo
+    async fn load(&self) -> Result<Arc<Vec<FeatureFlag>>, FeatureFlagRepoError> {
+        let ttl = Duration::from_secs(self.config.current().feature_flags.cache_ttl_secs);
+        let stale = match self.cached(ttl) {
+            Some((flags, true)) => return Ok(flags),
+            Some((flags, false)) => Some(flags),
+            None => None,
+        };
+
+        match self.inner.find_all().await {
+            Ok(flags) => {
+                let flags = Arc::new(flags);
+                self.store(Some(flags.clone()));
+                Ok(flags)
+            }
+            Err(e) => match stale {
+                Some(flags) => {
+                    tracing::warn!(error = %e, "Could not refresh feature flags, using the previous ones");
+                    // Se reintenta al caducar de nuevo, no en cada request
+                    self.store(Some(flags.clone()));
+                    Ok(flags)
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<R: FeatureFlagRepository> FeatureFlagRepository for CachedFeatureFlagRepository<R> {
+    async fn find_all(&self) -> Result<Vec<FeatureFlag>, FeatureFlagRepoError> {
+        self.load().await.map(|flags| flags.as_ref().clone())
+    }
+
+    async fn upsert(&self, key: &str, update: &FeatureFlagUpdate) -> Result<FeatureFlag, FeatureFlagRepoError> {
+        let flag = self.inner.upsert(key, update).await?;
+        self.store(None);
+        Ok(flag)
+    }
+}
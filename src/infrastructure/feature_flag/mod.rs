@@ -0,0 +1,2 @@
+pub mod cached;
+pub mod mongo;
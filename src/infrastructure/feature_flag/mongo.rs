@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt; // Necesario para try_collect()
+use mongodb::Database;
+use mongodb::bson::{doc, DateTime as BsonDateTime, Document};
+use mongodb::options::ReturnDocument;
+
+use crate::domain::{FeatureFlag, FeatureFlagRepository, FeatureFlagRepoError, FeatureFlagUpdate};
+use crate::mappers::feature_flag::doc_to_domain;
+use crate::infrastructure::indexes::RequiredIndex;
+use crate::metrics::observe_mongo;
+
+const REPOSITORY: &str = "MongoFeatureFlagRepository";
+
+/// Flags en la colección `FeatureFlag` de NotificationDB (un documento por `key`)
+#[derive(Clone)]
+pub struct MongoFeatureFlagRepository {
+    db: Database,
+}
+
+impl MongoFeatureFlagRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Índices que necesitan las consultas (ver `IndexManager`)
+    /// Único para que dos upserts simultáneos del mismo flag no creen dos documentos
+    pub fn required_indexes() -> Vec<RequiredIndex> {
+        vec![RequiredIndex::new("FeatureFlag", doc! { "key": 1 }).unique()]
+    }
+}
+
+#[async_trait]
+impl FeatureFlagRepository for MongoFeatureFlagRepository {
+    async fn find_all(&self) -> Result<Vec<FeatureFlag>, FeatureFlagRepoError> {
+        let coll = self.db.collection::<Document>("FeatureFlag");
+        let docs: Vec<Document> = observe_mongo(REPOSITORY, "find_all", async {
+            coll.find(doc! {}).sort(doc! { "key": 1 }).await?.try_collect::<Vec<_>>().await
+        })
+            .await
+            .map_err(|e| FeatureFlagRepoError::Unexpected(e.to_string()))?;
+
+        // Un documento mal formado no debe desactivar el resto de flags
+        Ok(docs
+            .into_iter()
+            .filter_map(|doc| {
+                doc_to_domain(doc)
+                    .inspect_err(|e| tracing::warn!(repository = REPOSITORY, error = %e, "Skipping invalid feature flag"))
+                    .ok()
+            })
+            .collect())
+    }
+
+    async fn upsert(&self, key: &str, update: &FeatureFlagUpdate) -> Result<FeatureFlag, FeatureFlagRepoError> {
+        let coll = self.db.collection::<Document>("FeatureFlag");
+        let mut set = doc! { "enabled": update.enabled, "updatedAt": BsonDateTime::now() };
+        let mut set_on_insert = doc! { "description": "", "businessIds": [], "accountTypeIds": [] };
+        match update.percentage {
+            Some(percentage) => set.insert("percentage", percentage as i32),
+            None => set_on_insert.insert("percentage", 100),
+        };
+
+        let query = coll
+            .find_one_and_update(doc! { "key": key }, doc! { "$set": set, "$setOnInsert": set_on_insert })
+            .upsert(true)
+            .return_document(ReturnDocument::After);
+        let doc = observe_mongo(REPOSITORY, "upsert", query)
+            .await
+            .map_err(|e| FeatureFlagRepoError::Unexpected(e.to_string()))?
+            .ok_or_else(|| FeatureFlagRepoError::Unexpected("upsert returned no document".to_string()))?;
+
+        doc_to_domain(doc)
+    }
+}
@@ -0,0 +1,190 @@
+use futures::future::join_all;
+use mongodb::bson::doc;
+use mongodb::Database;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::{ConfigHandle, GetStreamConfig};
+use crate::infrastructure::db::Databases;
+use crate::infrastructure::providers::StorageServiceProvider;
+use crate::infrastructure::storage::StorageObject;
+
+/// Estado de una dependencia en el readiness check
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyStatus {
+    Up,
+    Down,
+}
+
+/// Resultado de comprobar una dependencia
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCheck {
+    pub name: String,
+    pub status: DependencyStatus,
+    pub latency_ms: u64,
+}
+
+impl DependencyCheck {
+    /// El error solo va a los logs: `/health/ready` es público y no debe exponer errores de los drivers
+    fn new(name: impl Into<String>, latency: Duration, result: Result<(), String>) -> Self {
+        let name = name.into();
+        let status = match result {
+            Ok(()) => DependencyStatus::Up,
+            Err(e) => {
+                tracing::warn!(dependency = %name, error = %e, "Readiness check failed");
+                DependencyStatus::Down
+            }
+        };
+        Self { name, status, latency_ms: latency.as_millis() as u64 }
+    }
+}
+
+/// Resultado del readiness check: listo solo si todas las dependencias están arriba
+#[derive(Clone, Debug, Serialize)]
+pub struct ReadinessReport {
+    pub status: &'static str,
+    pub dependencies: Vec<DependencyCheck>,
+}
+
+impl ReadinessReport {
+    fn new(dependencies: Vec<DependencyCheck>) -> Self {
+        let ready = dependencies.iter().all(|d| d.status == DependencyStatus::Up);
+        Self { status: if ready { "ready" } else { "unavailable" }, dependencies }
+    }
+
+    fn shutting_down() -> Self {
+        Self { status: "shutting_down", dependencies: Vec::new() }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.status == "ready"
+    }
+}
+
+/// Comprueba las dependencias reales del servicio para `/health/ready`
+/// - MongoDB: `ping` a cada base de datos de `Databases`
+/// - GetStream y cola: que la configuración (`AppConfig`) esté presente y sea válida (sin llamarlos)
+/// - Almacenamiento (opcional): firma de una URL de prueba
+#[derive(Clone)]
+pub struct ReadinessChecker {
+    databases: Vec<Database>,
+    storage: StorageServiceProvider,
+    /// Timeout y sonda de almacenamiento se leen en cada comprobación (`[readiness]`, recargable)
+    config: ConfigHandle,
+    /// Compartido entre workers: al apagar, `/health/ready` falla sin comprobar nada
+    shutting_down: Arc<AtomicBool>,
+}
+
+impl ReadinessChecker {
+    pub fn new(databases: &Databases, storage: StorageServiceProvider, config: &ConfigHandle) -> Self {
+        Self {
+            databases: vec![
+                databases.notifications_db.clone(),
+                databases.account_db.clone(),
+                databases.analytics_db.clone(),
+                databases.client_db.clone(),
+            ],
+            storage,
+            config: config.clone(),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Marca el servicio como no disponible para que el balanceador deje de enviarle tráfico
+    pub fn mark_shutting_down(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Ejecuta todas las comprobaciones en paralelo
+    pub async fn check(&self) -> ReadinessReport {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return ReadinessReport::shutting_down();
+        }
+
+        let config = self.config.current();
+        let timeout = Duration::from_millis(config.readiness.timeout_ms);
+        let mongo = join_all(self.databases.iter().map(|db| {
+            let name = format!("mongodb:{}", db.name());
+            timed(name, timeout, async move {
+                db.run_command(doc! { "ping": 1 })
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            })
+        }));
+        let storage = async {
+            if !config.readiness.probe_storage {
+                return None;
+            }
+            let probe = StorageObject { key: "healthcheck/readiness".to_string(), bucket: None };
+            Some(timed("storage".to_string(), timeout, async {
+                self.storage.signer.sign_url(&probe, 60).await.map(|_| ())
+            }).await)
+        };
+        let (mut dependencies, storage) = tokio::join!(mongo, storage);
+
+        dependencies.push(check_getstream_config(&config.getstream));
+        dependencies.push(check_queue_config(&config.queue.url));
+        dependencies.extend(storage);
+
+        ReadinessReport::new(dependencies)
+    }
+}
+
+async fn timed(name: String, timeout: Duration, check: impl Future<Output = Result<(), String>>) -> DependencyCheck {
+    let start = Instant::now();
+    let result = match tokio::time::timeout(timeout, check).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+    DependencyCheck::new(name, start.elapsed(), result)
+}
+
+/// GetStream necesita API key y secreto (para firmar los JWT)
+fn check_getstream_config(config: &GetStreamConfig) -> DependencyCheck {
+    let missing: Vec<&str> = [("GETSTREAM_API_KEY", config.api_key.as_str()), ("GETSTREAM_SECRET", config.secret.expose())]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(name, _)| name)
+        .collect();
+    let result = if missing.is_empty() { Ok(()) } else { Err(format!("missing {}", missing.join(", "))) };
+    DependencyCheck::new("getstream", Duration::ZERO, result)
+}
+
+/// La URL de la cola debe ser http(s) válida
+fn check_queue_config(queue_url: &str) -> DependencyCheck {
+    let result = match url::Url::parse(queue_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        Ok(url) => Err(format!("unsupported QUEUE_URL scheme '{}'", url.scheme())),
+        Err(e) => Err(format!("invalid QUEUE_URL: {}", e)),
+    };
+    DependencyCheck::new("queue", Duration::ZERO, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Secret;
+
+    #[test]
+    fn reports_missing_configuration_as_not_ready() {
+        let getstream = check_getstream_config(&GetStreamConfig { api_key: "key".to_string(), secret: Secret::new(" ") });
+        assert_eq!(getstream.status, DependencyStatus::Down);
+
+        let queue = check_queue_config("https://community.goil.app/api/v2/queue");
+        assert_eq!(queue.status, DependencyStatus::Up);
+        assert_eq!(check_queue_config("community.goil.app").status, DependencyStatus::Down);
+
+        assert!(ReadinessReport::new(vec![queue.clone()]).is_ready());
+        let report = ReadinessReport::new(vec![queue, getstream]);
+        assert!(!report.is_ready());
+        let body = serde_json::to_value(&report).unwrap();
+        assert_eq!(body["dependencies"][1]["status"], "down");
+        assert!(body["dependencies"][1].get("error").is_none());
+    }
+}
@@ -0,0 +1,134 @@
+use futures::future::join_all;
+use std::sync::Arc;
+
+use crate::config::ImageVariantsConfig;
+use crate::infrastructure::storage::{is_absolute_url, ImageUrlSigner, StorageObject};
+use crate::types::ImageHint;
+
+/// Convención de nombres de las variantes pregeneradas de cada imagen
+///
+/// Los patrones se aplican sobre la key ya resuelta (ver `KeyRewriter`) con los
+/// placeholders `{dir}`, `{name}`, `{ext}` y `{density}`. Para "notifications/images/promo.png":
+/// - densidad (default `{dir}/{name}@{density}x.{ext}`): "notifications/images/promo@2x.png"
+/// - miniatura (default `{dir}/thumb/{name}.{ext}`): "notifications/images/thumb/promo.png"
+pub struct ImageVariantConvention {
+    density_pattern: String,
+    thumb_pattern: String,
+    /// Ancho máximo (píxeles lógicos) para el que se sirve la miniatura
+    thumb_max_width: u32,
+}
+
+impl ImageVariantConvention {
+    /// Crea la convención con `storage.variants`
+    pub fn from_config(config: &ImageVariantsConfig) -> Self {
+        Self {
+            density_pattern: config.density_pattern.clone(),
+            thumb_pattern: config.thumb_pattern.clone(),
+            thumb_max_width: config.thumb_max_width,
+        }
+    }
+
+    /// Keys de las variantes a probar, por orden de preferencia (el original no se incluye)
+    pub fn candidates(&self, key: &str, hint: &ImageHint) -> Vec<String> {
+        if is_absolute_url(key) {
+            return Vec::new();
+        }
+
+        let mut candidates = Vec::new();
+        if hint.width.is_some_and(|w| w <= self.thumb_max_width) {
+            candidates.extend(apply_pattern(&self.thumb_pattern, key, hint.density.unwrap_or(1)));
+        }
+        if let Some(density) = hint.density {
+            candidates.extend(apply_pattern(&self.density_pattern, key, density));
+        }
+        candidates
+    }
+}
+
+/// Sustituye los placeholders del patrón; None si la key no tiene extensión
+fn apply_pattern(pattern: &str, key: &str, density: u8) -> Option<String> {
+    let (dir, file) = key.rsplit_once('/').unwrap_or(("", key));
+    let (name, ext) = file.rsplit_once('.')?;
+    if name.is_empty() {
+        return None;
+    }
+
+    let variant = pattern
+        .replace("{dir}", dir)
+        .replace("{name}", name)
+        .replace("{ext}", ext)
+        .replace("{density}", &density.to_string());
+    Some(variant.trim_start_matches('/').to_string())
+}
+
+/// Elige, para cada imagen, la mejor variante existente según la pista del cliente
+/// La existencia se comprueba en el backend de almacenamiento, que la cachea (ver `ExistenceCache`)
+/// para no lanzar un HeadObject por variante en cada request
+#[derive(Clone)]
+pub struct ImageVariantResolver {
+    convention: Arc<ImageVariantConvention>,
+}
+
+impl ImageVariantResolver {
+    pub fn from_config(config: &ImageVariantsConfig) -> Self {
+        Self {
+            convention: Arc::new(ImageVariantConvention::from_config(config)),
+        }
+    }
+
+    /// Devuelve, en el mismo orden, la mejor variante de cada imagen o la original
+    pub async fn resolve(&self, signer: &dyn ImageUrlSigner, objects: &[StorageObject], hint: &ImageHint) -> Vec<StorageObject> {
+        if hint.is_empty() || !signer.can_check_existence() {
+            return objects.to_vec();
+        }
+
+        join_all(objects.iter().map(|object| async move {
+            for candidate in self.convention.candidates(&object.key, hint) {
+                let candidate = object.with_key(candidate);
+                match signer.object_exists(&candidate).await {
+                    Ok(true) => return candidate,
+                    Ok(false) => {}
+                    // Ante errores del almacenamiento la variante se descarta en este request
+                    Err(e) => tracing::warn!(key = %candidate.key, error = %e, "Could not check image variant existence"),
+                }
+            }
+            object.clone()
+        }))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convention() -> ImageVariantConvention {
+        ImageVariantConvention {
+            density_pattern: "{dir}/{name}@{density}x.{ext}".to_string(),
+            thumb_pattern: "{dir}/thumb/{name}.{ext}".to_string(),
+            thumb_max_width: 320,
+        }
+    }
+
+    #[test]
+    fn builds_candidates_from_normalized_key() {
+        let hint = ImageHint { density: Some(2), width: Some(150) };
+        assert_eq!(
+            convention().candidates("notifications/images/promo.png", &hint),
+            vec![
+                "notifications/images/thumb/promo.png".to_string(),
+                "notifications/images/promo@2x.png".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_thumbnail_for_wide_images_and_keys_without_extension() {
+        let hint = ImageHint { density: Some(3), width: Some(1080) };
+        assert_eq!(
+            convention().candidates("notifications/images/promo.jpg", &hint),
+            vec!["notifications/images/promo@3x.jpg".to_string()]
+        );
+        assert!(convention().candidates("notifications/images/promo", &hint).is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use futures::stream::TryStreamExt; // Necesario para try_collect()
+use mongodb::bson::{Bson, Document};
+use mongodb::error::ErrorKind;
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+
+use crate::infrastructure::analytics::mongo::MongoNotificationReadRepository;
+use crate::infrastructure::db::Databases;
+use crate::infrastructure::feature_flag::mongo::MongoFeatureFlagRepository;
+use crate::infrastructure::notification::mongo::MongoNotificationRepository;
+use crate::infrastructure::session::mongo::MongoSessionRepository;
+use crate::infrastructure::user::mongo::MongoUserRepository;
+
+/// Índice que necesita un repositorio (ver `required_indexes` de cada repositorio de MongoDB)
+#[derive(Clone, Debug)]
+pub struct RequiredIndex {
+    pub collection: &'static str,
+    pub keys: Document,
+    /// Nombre al crearlo; sin él MongoDB lo genera a partir de las keys ("phone_1_businessId_1")
+    pub name: Option<&'static str>,
+    pub unique: bool,
+}
+
+impl RequiredIndex {
+    pub fn new(collection: &'static str, keys: Document) -> Self {
+        Self { collection, keys, name: None, unique: false }
+    }
+
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    fn model(&self) -> IndexModel {
+        let options = IndexOptions::builder()
+            .name(self.name.map(String::from))
+            .unique(self.unique.then_some(true))
+            .build();
+        IndexModel::builder().keys(self.keys.clone()).options(options).build()
+    }
+}
+
+/// Estado de un índice requerido en la base de datos
+#[derive(Clone, Debug, PartialEq)]
+pub enum IndexStatus {
+    Present,
+    Missing,
+    /// Existe un índice con las mismas keys (o el mismo nombre) pero no es el declarado
+    Different(String),
+    /// No existía y se ha creado (`--ensure-indexes`)
+    Created,
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexCheck {
+    pub database: String,
+    pub index: RequiredIndex,
+    pub status: IndexStatus,
+}
+
+/// Resultado de comparar los índices declarados con los existentes
+#[derive(Debug, Default)]
+pub struct IndexReport {
+    pub checks: Vec<IndexCheck>,
+}
+
+impl IndexReport {
+    /// Índices que faltan o no coinciden con la declaración
+    pub fn problems(&self) -> impl Iterator<Item = &IndexCheck> {
+        self.checks
+            .iter()
+            .filter(|check| matches!(check.status, IndexStatus::Missing | IndexStatus::Different(_)))
+    }
+
+    pub fn count(&self, status: fn(&IndexStatus) -> bool) -> usize {
+        self.checks.iter().filter(|check| status(&check.status)).count()
+    }
+
+    /// Registra cada diferencia y un resumen
+    pub fn log(&self) {
+        for check in &self.checks {
+            let keys = check.index.keys.to_string();
+            match &check.status {
+                IndexStatus::Present => {
+                    tracing::debug!(database = %check.database, collection = check.index.collection, keys = %keys, "MongoDB index present")
+                }
+                IndexStatus::Created => {
+                    tracing::info!(database = %check.database, collection = check.index.collection, keys = %keys, "MongoDB index created")
+                }
+                IndexStatus::Missing => {
+                    tracing::warn!(database = %check.database, collection = check.index.collection, keys = %keys, "MongoDB index missing")
+                }
+                IndexStatus::Different(difference) => tracing::warn!(
+                    database = %check.database,
+                    collection = check.index.collection,
+                    keys = %keys,
+                    difference = %difference,
+                    "MongoDB index differs from the declared one"
+                ),
+            }
+        }
+        tracing::info!(
+            present = self.count(|s| *s == IndexStatus::Present),
+            created = self.count(|s| *s == IndexStatus::Created),
+            missing = self.count(|s| *s == IndexStatus::Missing),
+            different = self.count(|s| matches!(s, IndexStatus::Different(_))),
+            "MongoDB indexes checked"
+        );
+    }
+}
+
+/// Gestor de los índices que necesitan los repositorios
+///
+/// Al arrancar se verifican (`mongodb.index_check`: off, warn o fail) o, con `--ensure-indexes`, se crean
+/// los que faltan. Los índices distintos de los declarados nunca se modifican ni se borran: solo se reportan.
+pub struct IndexManager {
+    declared: Vec<(Database, RequiredIndex)>,
+}
+
+impl IndexManager {
+    pub fn new(databases: &Databases) -> Self {
+        let mut declared = Vec::new();
+        for (db, indexes) in [
+            (&databases.account_db, MongoUserRepository::required_indexes()),
+            (&databases.account_db, MongoSessionRepository::required_indexes()),
+            (&databases.notifications_db, MongoNotificationRepository::required_indexes()),
+            (&databases.notifications_db, MongoFeatureFlagRepository::required_indexes()),
+            (&databases.analytics_db, MongoNotificationReadRepository::required_indexes()),
+        ] {
+            declared.extend(indexes.into_iter().map(|index| (db.clone(), index)));
+        }
+        Self { declared }
+    }
+
+    /// Compara los índices declarados con los existentes sin modificar nada
+    pub async fn verify(&self) -> Result<IndexReport, String> {
+        let mut report = IndexReport::default();
+        for (db, index) in &self.declared {
+            let existing = existing_indexes(db, index.collection).await?;
+            report.checks.push(IndexCheck {
+                database: db.name().to_string(),
+                index: index.clone(),
+                status: index_status(index, &existing),
+            });
+        }
+        Ok(report)
+    }
+
+    /// Crea los índices que faltan; el resto de diferencias se reportan
+    pub async fn ensure(&self) -> Result<IndexReport, String> {
+        let mut report = self.verify().await?;
+        for (check, (db, index)) in report.checks.iter_mut().zip(&self.declared) {
+            if check.status != IndexStatus::Missing {
+                continue;
+            }
+            db.collection::<Document>(index.collection)
+                .create_index(index.model())
+                .await
+                .map_err(|e| format!("Could not create index {} on {}.{}: {}", index.keys, db.name(), index.collection, e))?;
+            check.status = IndexStatus::Created;
+        }
+        Ok(report)
+    }
+}
+
+async fn existing_indexes(db: &Database, collection: &str) -> Result<Vec<IndexModel>, String> {
+    let coll = db.collection::<Document>(collection);
+    let listed = async { coll.list_indexes().await?.try_collect::<Vec<_>>().await };
+    match listed.await {
+        Ok(indexes) => Ok(indexes),
+        // La colección aún no existe: no tiene índices
+        Err(e) if matches!(*e.kind, ErrorKind::Command(ref err) if err.code == 26) => Ok(Vec::new()),
+        Err(e) => Err(format!("Could not list indexes of {}.{}: {}", db.name(), collection, e)),
+    }
+}
+
+/// Las keys se comparan en orden; la dirección como número (1, 1i64 y 1.0 son la misma)
+fn same_keys(a: &Document, b: &Document) -> bool {
+    let normalize = |value: &Bson| match value {
+        Bson::Int32(n) => Bson::Double(*n as f64),
+        Bson::Int64(n) => Bson::Double(*n as f64),
+        other => other.clone(),
+    };
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|((ka, va), (kb, vb))| ka == kb && normalize(va) == normalize(vb))
+}
+
+fn index_status(required: &RequiredIndex, existing: &[IndexModel]) -> IndexStatus {
+    let name_of = |model: &IndexModel| model.options.as_ref().and_then(|o| o.name.clone()).unwrap_or_default();
+
+    if let Some(model) = existing.iter().find(|model| same_keys(&model.keys, &required.keys)) {
+        let unique = model.options.as_ref().and_then(|o| o.unique).unwrap_or(false);
+        return match (required.unique, unique) {
+            (true, false) => IndexStatus::Different(format!("index '{}' is not unique", name_of(model))),
+            _ => IndexStatus::Present,
+        };
+    }
+
+    match required.name.and_then(|name| existing.iter().find(|model| name_of(model) == name)) {
+        Some(model) => IndexStatus::Different(format!("index name '{}' is used with keys {}", name_of(model), model.keys)),
+        None => IndexStatus::Missing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    fn existing(keys: Document, name: &str, unique: Option<bool>) -> IndexModel {
+        IndexModel::builder()
+            .keys(keys)
+            .options(IndexOptions::builder().name(name.to_string()).unique(unique).build())
+            .build()
+    }
+
+    #[test]
+    fn compares_keys_in_order_and_reports_differences() {
+        let required = RequiredIndex::new("Account", doc! { "phone": 1, "businessId": 1 }).named("phone_businessId_idx");
+
+        let same = [existing(doc! { "phone": 1i64, "businessId": 1.0 }, "phone_1_businessId_1", None)];
+        assert_eq!(index_status(&required, &same), IndexStatus::Present);
+
+        let reversed = [existing(doc! { "businessId": 1, "phone": 1 }, "businessId_1_phone_1", None)];
+        assert_eq!(index_status(&required, &reversed), IndexStatus::Missing);
+
+        let name_taken = [existing(doc! { "phone": 1 }, "phone_businessId_idx", None)];
+        assert!(matches!(index_status(&required, &name_taken), IndexStatus::Different(_)));
+
+        let unique = RequiredIndex::new("FeatureFlag", doc! { "key": 1 }).unique();
+        assert!(matches!(index_status(&unique, &[existing(doc! { "key": 1 }, "key_1", None)]), IndexStatus::Different(_)));
+        assert_eq!(index_status(&unique, &[existing(doc! { "key": 1 }, "key_1", Some(true))]), IndexStatus::Present);
+    }
+}
@@ -0,0 +1,252 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::config::StorageConfig;
+use crate::infrastructure::storage::{is_absolute_url, StorageObject};
+
+/// Regla de reescritura tal como se escribe en la configuración (JSON)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum KeyRuleConfig {
+    /// Sustituye el prefijo `from` por `to`
+    #[serde(rename_all = "camelCase")]
+    Prefix {
+        from: String,
+        to: String,
+        #[serde(default)]
+        business_ids: Vec<String>,
+    },
+    /// Aplica `pattern` y sustituye con `replacement` (admite grupos `$1`, `${name}`)
+    #[serde(rename_all = "camelCase")]
+    Regex {
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        business_ids: Vec<String>,
+    },
+}
+
+/// Configuración completa de reescritura de keys
+/// ```json
+/// {
+///   "rules": [
+///     { "type": "prefix", "from": "notification/image/", "to": "notifications/images/" },
+///     { "type": "regex", "pattern": "^uploads/(\\w+)/img/(.*)$", "replacement": "notifications/images/$1/$2",
+///       "businessIds": ["5f1b..."] }
+///   ],
+///   "businessBuckets": { "5f1b...": "goil-club-public" }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRulesConfig {
+    #[serde(default)]
+    pub rules: Vec<KeyRuleConfig>,
+    #[serde(default)]
+    pub business_buckets: HashMap<String, String>,
+}
+
+impl KeyRulesConfig {
+    /// Reglas históricas: "notification/image/", "notification/images/" y "notifications/image/"
+    /// se guardan en S3 bajo "notifications/images/"
+    pub fn legacy() -> Self {
+        let prefix = |from: &str| KeyRuleConfig::Prefix {
+            from: from.to_string(),
+            to: "notifications/images/".to_string(),
+            business_ids: Vec::new(),
+        };
+        Self {
+            rules: vec![
+                prefix("notification/image/"),
+                prefix("notification/images/"),
+                prefix("notifications/image/"),
+            ],
+            business_buckets: HashMap::new(),
+        }
+    }
+}
+
+enum Matcher {
+    Prefix { from: String, to: String },
+    Regex { regex: Regex, replacement: String },
+}
+
+struct KeyRule {
+    matcher: Matcher,
+    /// Businesses a los que aplica la regla (vacío = todos)
+    business_ids: HashSet<String>,
+    description: String,
+}
+
+/// Resultado de resolver un path de imagen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyResolution {
+    pub object: StorageObject,
+    /// Índice y descripción de la regla aplicada (None si ninguna coincide)
+    pub rule: Option<(usize, String)>,
+}
+
+/// Motor de reescritura de keys de imágenes
+/// Se aplica la primera regla que coincide (en orden); si ninguna coincide la key queda igual.
+/// Las URLs completas (http/https) nunca se reescriben.
+pub struct KeyRewriter {
+    rules: Vec<KeyRule>,
+    business_buckets: HashMap<String, String>,
+}
+
+impl KeyRewriter {
+    /// Valida la configuración y compila las reglas; devuelve todos los errores encontrados
+    pub fn new(config: KeyRulesConfig) -> Result<Self, String> {
+        let mut errors = Vec::new();
+        let mut rules = Vec::with_capacity(config.rules.len());
+
+        for (index, rule) in config.rules.into_iter().enumerate() {
+            match rule {
+                KeyRuleConfig::Prefix { from, to, business_ids } => {
+                    if from.is_empty() {
+                        errors.push(format!("rule #{}: prefix 'from' must not be empty", index));
+                        continue;
+                    }
+                    rules.push(KeyRule {
+                        description: format!("prefix '{}' -> '{}'", from, to),
+                        matcher: Matcher::Prefix { from, to },
+                        business_ids: business_ids.into_iter().collect(),
+                    });
+                }
+                KeyRuleConfig::Regex { pattern, replacement, business_ids } => match Regex::new(&pattern) {
+                    Ok(regex) => rules.push(KeyRule {
+                        description: format!("regex '{}' -> '{}'", pattern, replacement),
+                        matcher: Matcher::Regex { regex, replacement },
+                        business_ids: business_ids.into_iter().collect(),
+                    }),
+                    Err(e) => errors.push(format!("rule #{}: invalid regex '{}': {}", index, pattern, e)),
+                },
+            }
+        }
+
+        for (business_id, bucket) in &config.business_buckets {
+            if bucket.trim().is_empty() {
+                errors.push(format!("businessBuckets: empty bucket for business '{}'", business_id));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(format!("Invalid S3 key rules: {}", errors.join("; ")));
+        }
+
+        Ok(Self {
+            rules,
+            business_buckets: config.business_buckets,
+        })
+    }
+
+    /// Carga las reglas de `storage.key_rules_file` (ruta a un JSON) o `storage.key_rules` (JSON inline)
+    /// Sin configuración se usan las reglas históricas (`KeyRulesConfig::legacy`)
+    pub fn from_config(config: &StorageConfig) -> Result<Self, String> {
+        let raw = match (&config.key_rules_file, &config.key_rules) {
+            (Some(path), _) => Some(
+                std::fs::read_to_string(path)
+                    .map_err(|e| format!("Error reading S3 key rules file '{}': {}", path.display(), e))?,
+            ),
+            (None, Some(inline)) => Some(inline.clone()),
+            _ => None,
+        };
+
+        let config = match raw {
+            Some(raw) => serde_json::from_str::<KeyRulesConfig>(&raw)
+                .map_err(|e| format!("Invalid S3 key rules JSON: {}", e))?,
+            None => KeyRulesConfig::legacy(),
+        };
+
+        Self::new(config)
+    }
+
+    /// Resuelve el path guardado en la notificación a la key (y bucket) real del almacenamiento
+    pub fn resolve(&self, business_id: Option<&str>, path: &str) -> KeyResolution {
+        let bucket = business_id.and_then(|id| self.business_buckets.get(id)).cloned();
+        if is_absolute_url(path) {
+            return KeyResolution { object: StorageObject { key: path.to_string(), bucket }, rule: None };
+        }
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            let applies = rule.business_ids.is_empty()
+                || business_id.is_some_and(|id| rule.business_ids.contains(id));
+            if !applies {
+                continue;
+            }
+
+            let rewritten = match &rule.matcher {
+                Matcher::Prefix { from, to } => path.strip_prefix(from.as_str()).map(|rest| format!("{}{}", to, rest)),
+                Matcher::Regex { regex, replacement } => regex
+                    .is_match(path)
+                    .then(|| regex.replace(path, replacement.as_str()).into_owned()),
+            };
+
+            if let Some(key) = rewritten {
+                return KeyResolution {
+                    object: StorageObject { key, bucket },
+                    rule: Some((index, rule.description.clone())),
+                };
+            }
+        }
+
+        KeyResolution { object: StorageObject { key: path.to_string(), bucket }, rule: None }
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_rules_match_historical_prefixes() {
+        let rewriter = KeyRewriter::new(KeyRulesConfig::legacy()).unwrap();
+        for path in ["notification/image/a.png", "notification/images/a.png", "notifications/image/a.png"] {
+            assert_eq!(rewriter.resolve(None, path).object.key, "notifications/images/a.png");
+        }
+        let untouched = rewriter.resolve(None, "notifications/images/a.png");
+        assert_eq!(untouched.object.key, "notifications/images/a.png");
+        assert_eq!(untouched.rule, None);
+        assert_eq!(rewriter.resolve(None, "https://cdn.goil.app/a.png").object.key, "https://cdn.goil.app/a.png");
+    }
+
+    #[test]
+    fn applies_business_scoped_regex_and_bucket_override() {
+        let config: KeyRulesConfig = serde_json::from_str(r#"{
+            "rules": [
+                { "type": "regex", "pattern": "^uploads/(\\w+)/img/(.*)$", "replacement": "notifications/images/$1/$2", "businessIds": ["b1"] }
+            ],
+            "businessBuckets": { "b1": "club-public" }
+        }"#).unwrap();
+        let rewriter = KeyRewriter::new(config).unwrap();
+
+        let resolved = rewriter.resolve(Some("b1"), "uploads/promo/img/a.png");
+        assert_eq!(resolved.object.key, "notifications/images/promo/a.png");
+        assert_eq!(resolved.object.bucket.as_deref(), Some("club-public"));
+        assert_eq!(resolved.rule.map(|(index, _)| index), Some(0));
+
+        let other = rewriter.resolve(Some("b2"), "uploads/promo/img/a.png");
+        assert_eq!(other.object.key, "uploads/promo/img/a.png");
+        assert_eq!(other.object.bucket, None);
+    }
+
+    #[test]
+    fn reports_all_invalid_rules() {
+        let config: KeyRulesConfig = serde_json::from_str(r#"{
+            "rules": [
+                { "type": "prefix", "from": "", "to": "x/" },
+                { "type": "regex", "pattern": "([", "replacement": "" }
+            ],
+            "businessBuckets": { "b1": " " }
+        }"#).unwrap();
+        let err = KeyRewriter::new(config).err().unwrap();
+        assert!(err.contains("rule #0"));
+        assert!(err.contains("rule #1"));
+        assert!(err.contains("business 'b1'"));
+    }
+}
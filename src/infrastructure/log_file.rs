@@ -0,0 +1,257 @@
+use prometheus::{register_int_counter, IntCounter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+use crate::config::LogFileConfig;
+
+/// Líneas de log que no se pudieron escribir en el fichero (buffer lleno o error de escritura)
+pub static LOG_FILE_DROPPED_ENTRIES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("log_file_dropped_entries_total", "Log entries dropped before reaching the log file")
+        .expect("metric registered twice")
+});
+
+/// Fichero de logs con rotación por tamaño y por tiempo, y retención de los rotados
+/// (sección `[logging.file]`, validada al cargar la configuración; formato JSON por línea, el que lee promtail)
+///
+/// Los rotados se renombran a `{name}.log.{YYYYMMDDTHHMMSS}` para que el glob `*.log` de promtail
+/// no los vuelva a leer.
+struct RotatingFile {
+    config: LogFileConfig,
+    writer: Option<BufWriter<File>>,
+    size: u64,
+    opened_at: SystemTime,
+}
+
+impl RotatingFile {
+    fn new(config: LogFileConfig) -> Self {
+        Self { config, writer: None, size: 0, opened_at: SystemTime::UNIX_EPOCH }
+    }
+
+    fn current_path(&self) -> PathBuf {
+        self.config.dir.join(format!("{}.log", self.config.name))
+    }
+
+    fn open(&mut self, now: SystemTime) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.config.dir)?;
+        let file = OpenOptions::new().create(true).append(true).open(self.current_path())?;
+        self.size = file.metadata()?.len();
+        self.opened_at = now;
+        self.writer = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str, now: SystemTime) -> std::io::Result<()> {
+        if self.writer.is_none() {
+            self.open(now)?;
+        }
+
+        let len = line.len() as u64 + 1;
+        let too_big = self.size > 0 && self.size + len > self.config.max_bytes;
+        let too_old = now.duration_since(self.opened_at).unwrap_or_default() >= Duration::from_secs(self.config.rotate_secs);
+        if too_big || too_old {
+            self.rotate(now)?;
+        }
+
+        let writer = self.writer.as_mut().expect("log file opened");
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn rotate(&mut self, now: SystemTime) -> std::io::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+
+        if self.size > 0 {
+            let stamp = chrono::DateTime::<chrono::Utc>::from(now).format("%Y%m%dT%H%M%S");
+            let base = format!("{}.log.{}", self.config.name, stamp);
+            let mut rotated = self.config.dir.join(&base);
+            let mut n = 1;
+            while rotated.exists() {
+                rotated = self.config.dir.join(format!("{}-{}", base, n));
+                n += 1;
+            }
+            std::fs::rename(self.current_path(), rotated)?;
+        }
+
+        self.open(now)?;
+        self.apply_retention(now)
+    }
+
+    /// Borra los rotados que exceden `max_files` o `max_age` (el fichero actual nunca se borra)
+    fn apply_retention(&self, now: SystemTime) -> std::io::Result<()> {
+        let prefix = format!("{}.log.", self.config.name);
+        let mut rotated: Vec<(PathBuf, SystemTime)> = std::fs::read_dir(&self.config.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(now);
+                (entry.path(), modified)
+            })
+            .collect();
+
+        // Más recientes primero
+        rotated.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+        for (index, (path, modified)) in rotated.iter().enumerate() {
+            let expired = now.duration_since(*modified).unwrap_or_default() > Duration::from_secs(self.config.max_age_secs);
+            if index >= self.config.max_files || expired {
+                remove_quietly(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn remove_quietly(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        tracing::warn!(path = %path.display(), error = %e, "Error removing rotated log file");
+    }
+}
+
+enum FileSinkMessage {
+    Line(String),
+    /// Confirma cuando todo lo recibido antes está escrito en disco
+    Flush(std::sync::mpsc::Sender<()>),
+}
+
+/// Escribe las líneas de log en fichero desde un hilo dedicado
+/// `push` no bloquea: si el hilo no da abasto se descartan líneas y se cuentan en métricas
+#[derive(Clone)]
+pub struct FileLogSink {
+    tx: SyncSender<FileSinkMessage>,
+}
+
+impl FileLogSink {
+    pub fn start(config: LogFileConfig) -> std::io::Result<Self> {
+        let (tx, rx) = sync_channel::<FileSinkMessage>(config.buffer);
+        let mut file = RotatingFile::new(config);
+        // Abrir ya el fichero para detectar permisos o rutas inválidas al arrancar
+        file.open(SystemTime::now())?;
+
+        std::thread::Builder::new()
+            .name("log-file-sink".to_string())
+            .spawn(move || {
+                while let Ok(message) = rx.recv() {
+                    // Escribir todo lo pendiente y hacer un único flush
+                    let mut acks = Vec::new();
+                    for message in std::iter::once(message).chain(rx.try_iter()) {
+                        match message {
+                            FileSinkMessage::Line(line) => {
+                                if let Err(e) = file.write_line(&line, SystemTime::now()) {
+                                    tracing::error!(error = %e, "Error writing access log line");
+                                    LOG_FILE_DROPPED_ENTRIES.inc();
+                                }
+                            }
+                            FileSinkMessage::Flush(ack) => acks.push(ack),
+                        }
+                    }
+                    if let Err(e) = file.flush() {
+                        tracing::error!(error = %e, "Error flushing access log file");
+                    }
+                    for ack in acks {
+                        let _ = ack.send(());
+                    }
+                }
+            })?;
+
+        Ok(Self { tx })
+    }
+
+    pub fn push(&self, line: String) {
+        if let Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) = self.tx.try_send(FileSinkMessage::Line(line)) {
+            LOG_FILE_DROPPED_ENTRIES.inc();
+        }
+    }
+
+    /// Espera (bloqueando, como máximo `timeout`) a que las líneas encoladas estén escritas en disco
+    pub fn flush(&self, timeout: Duration) -> bool {
+        let (ack_tx, ack_rx) = std::sync::mpsc::channel();
+        self.tx.send(FileSinkMessage::Flush(ack_tx)).is_ok() && ack_rx.recv_timeout(timeout).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path) -> LogFileConfig {
+        LogFileConfig {
+            enabled: true,
+            dir: dir.to_path_buf(),
+            name: "app".to_string(),
+            max_bytes: 20,
+            rotate_secs: 3600,
+            max_files: 2,
+            max_age_secs: 86_400,
+            buffer: 10,
+        }
+    }
+
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|n| n.starts_with("app.log."))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::new(config(dir.path()));
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        for i in 0..4 {
+            let now = start + Duration::from_secs(i);
+            file.write_line("0123456789abcdef", now).unwrap();
+        }
+        file.flush().unwrap();
+
+        // 4 líneas de 17 bytes con máximo 20: 3 rotaciones, se conservan los 2 rotados más recientes
+        let current = std::fs::read_to_string(dir.path().join("app.log")).unwrap();
+        assert_eq!(current, "0123456789abcdef\n");
+        assert_eq!(rotated_files(dir.path()).len(), 2);
+        assert!(rotated_files(dir.path()).iter().all(|n| !n.ends_with(".log")));
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingFile::new(LogFileConfig { max_bytes: 1_000, ..config(dir.path()) });
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        file.write_line("a", start).unwrap();
+        file.write_line("b", start + Duration::from_secs(60)).unwrap();
+        file.write_line("c", start + Duration::from_secs(3600)).unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(rotated_files(dir.path()), vec!["app.log.20231114T231320".to_string()]);
+        assert_eq!(std::fs::read_to_string(dir.path().join("app.log")).unwrap(), "c\n");
+    }
+
+    #[test]
+    fn flush_waits_until_queued_lines_are_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = FileLogSink::start(LogFileConfig { max_bytes: 1_000, ..config(dir.path()) }).unwrap();
+
+        sink.push("a".to_string());
+        sink.push("b".to_string());
+        assert!(sink.flush(Duration::from_secs(5)));
+        assert_eq!(std::fs::read_to_string(dir.path().join("app.log")).unwrap(), "a\nb\n");
+    }
+}
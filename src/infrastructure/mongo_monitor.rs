@@ -0,0 +1,192 @@
+use mongodb::bson::{Bson, Document};
+use mongodb::event::cmap::{CmapEvent, ConnectionCheckoutFailedReason};
+use mongodb::event::command::CommandEvent;
+use mongodb::event::EventHandler;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::metrics::{
+    MONGO_COMMAND_DURATION, MONGO_POOL_CHECKOUT_FAILURES, MONGO_POOL_CHECKOUT_WAIT, MONGO_SLOW_COMMANDS,
+};
+
+/// Campos del comando que contienen el filtro (o el pipeline) según el tipo de comando
+const FILTER_FIELDS: &[&str] = &["filter", "query", "q", "pipeline", "updates", "deletes"];
+
+/// Datos del comando guardados entre el evento de inicio y el de fin
+struct StartedCommand {
+    db: String,
+    collection: String,
+    filter_shape: String,
+}
+
+/// Monitorización de los comandos y del pool de conexiones de MongoDB
+///
+/// Alimenta `mongo_command_duration_seconds` (por colección) y `mongo_pool_checkout_wait_seconds`,
+/// y registra los comandos más lentos que `slow_threshold` con la forma del filtro (valores redactados).
+/// El driver solo incluye el comando en el evento de inicio, así que se guarda hasta el evento de fin.
+pub struct MongoMonitor {
+    slow_threshold: Duration,
+    in_flight: Mutex<HashMap<(String, u32, i32), StartedCommand>>,
+}
+
+impl MongoMonitor {
+    /// Crea el monitor con el umbral de comandos lentos (`mongodb.slow_query_ms`)
+    pub fn new(slow_threshold: Duration) -> Self {
+        Self {
+            slow_threshold,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Handler para `ClientOptions::command_event_handler`
+    pub fn command_handler(self: &Arc<Self>) -> EventHandler<CommandEvent> {
+        let monitor = self.clone();
+        EventHandler::callback(move |event| monitor.handle_command(event))
+    }
+
+    /// Handler para `ClientOptions::cmap_event_handler`
+    pub fn cmap_handler() -> EventHandler<CmapEvent> {
+        EventHandler::callback(|event| match event {
+            CmapEvent::ConnectionCheckedOut(e) => MONGO_POOL_CHECKOUT_WAIT.observe(e.duration.as_secs_f64()),
+            CmapEvent::ConnectionCheckoutFailed(e) => {
+                let reason = match e.reason {
+                    ConnectionCheckoutFailedReason::Timeout => "timeout",
+                    ConnectionCheckoutFailedReason::ConnectionError => "connection_error",
+                    _ => "other",
+                };
+                MONGO_POOL_CHECKOUT_FAILURES.with_label_values(&[reason]).inc();
+                tracing::warn!(
+                    address = %e.address,
+                    reason,
+                    wait_ms = e.duration.as_millis() as u64,
+                    "MongoDB pool checkout failed"
+                );
+            }
+            _ => {}
+        })
+    }
+
+    fn handle_command(&self, event: CommandEvent) {
+        match event {
+            CommandEvent::Started(e) => {
+                // Comandos sin colección (hello, ping, endSessions...) no se monitorizan
+                let Some(collection) = command_collection(&e.command, &e.command_name) else { return };
+                let started = StartedCommand {
+                    db: e.db,
+                    collection,
+                    filter_shape: filter_shape(&e.command),
+                };
+                if let Ok(mut in_flight) = self.in_flight.lock() {
+                    in_flight.insert((e.connection.address.to_string(), e.connection.id, e.request_id), started);
+                }
+            }
+            CommandEvent::Succeeded(e) => {
+                let key = (e.connection.address.to_string(), e.connection.id, e.request_id);
+                self.finish(key, &e.command_name, e.duration, None);
+            }
+            CommandEvent::Failed(e) => {
+                let key = (e.connection.address.to_string(), e.connection.id, e.request_id);
+                self.finish(key, &e.command_name, e.duration, Some(e.failure.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&self, key: (String, u32, i32), command: &str, duration: Duration, error: Option<String>) {
+        let started = self.in_flight.lock().ok().and_then(|mut in_flight| in_flight.remove(&key));
+        let Some(started) = started else { return };
+
+        let outcome = if error.is_some() { "error" } else { "success" };
+        MONGO_COMMAND_DURATION
+            .with_label_values(&[started.collection.as_str(), command, outcome])
+            .observe(duration.as_secs_f64());
+
+        if duration >= self.slow_threshold {
+            MONGO_SLOW_COMMANDS
+                .with_label_values(&[started.collection.as_str(), command])
+                .inc();
+            tracing::warn!(
+                db = %started.db,
+                collection = %started.collection,
+                command,
+                filter = %started.filter_shape,
+                duration_ms = duration.as_millis() as u64,
+                threshold_ms = self.slow_threshold.as_millis() as u64,
+                error = error.as_deref(),
+                "Slow MongoDB command"
+            );
+        }
+    }
+}
+
+/// Colección sobre la que opera el comando: el valor del primer campo (`{ find: "Notification", ... }`)
+/// o el campo `collection` en `getMore`
+fn command_collection(command: &Document, command_name: &str) -> Option<String> {
+    let value = if command_name == "getMore" {
+        command.get("collection")
+    } else {
+        command.get(command_name)
+    };
+    value.and_then(Bson::as_str).map(String::from)
+}
+
+/// Forma del filtro del comando con todos los valores sustituidos por "?"
+/// Ej: `{ "_id": "?", "businessId": { "$in": ["?"] } }`; "{}" si el comando no tiene filtro
+fn filter_shape(command: &Document) -> String {
+    FILTER_FIELDS
+        .iter()
+        .find_map(|field| command.get(field))
+        .map(|filter| redact(filter).to_string())
+        .unwrap_or_else(|| "{}".to_string())
+}
+
+/// Conserva claves y operadores y sustituye los valores; los arrays se reducen a sus formas distintas
+fn redact(value: &Bson) -> serde_json::Value {
+    match value {
+        Bson::Document(doc) => serde_json::Value::Object(
+            doc.iter().map(|(key, value)| (key.clone(), redact(value))).collect(),
+        ),
+        Bson::Array(items) => {
+            let mut shapes: Vec<serde_json::Value> = Vec::new();
+            for shape in items.iter().map(redact) {
+                if !shapes.contains(&shape) {
+                    shapes.push(shape);
+                }
+            }
+            serde_json::Value::Array(shapes)
+        }
+        _ => serde_json::Value::String("?".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn redacts_filter_values_but_keeps_shape() {
+        let command = doc! {
+            "find": "Account",
+            "filter": { "phone": "+34600123456", "businessId": { "$in": ["b1", "b2", "b3"] }, "deleted": false },
+            "limit": 100,
+        };
+        assert_eq!(command_collection(&command, "find").as_deref(), Some("Account"));
+
+        let shape = filter_shape(&command);
+        assert_eq!(shape, r#"{"phone":"?","businessId":{"$in":["?"]},"deleted":"?"}"#);
+        assert!(!shape.contains("600123456"));
+    }
+
+    #[test]
+    fn resolves_collection_for_aggregate_and_get_more() {
+        let aggregate = doc! { "aggregate": "NotificationRead", "pipeline": [{ "$match": { "phone": "x" } }] };
+        assert_eq!(command_collection(&aggregate, "aggregate").as_deref(), Some("NotificationRead"));
+        assert_eq!(filter_shape(&aggregate), r#"[{"$match":{"phone":"?"}}]"#);
+
+        let get_more = doc! { "getMore": 42_i64, "collection": "Notification" };
+        assert_eq!(command_collection(&get_more, "getMore").as_deref(), Some("Notification"));
+        assert_eq!(command_collection(&doc! { "ping": 1 }, "ping"), None);
+    }
+}
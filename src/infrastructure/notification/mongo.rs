@@ -7,6 +7,10 @@ use std::collections::HashSet;
 
 use crate::domain::{Notification, NotificationRepository, NotificationRepoError, SimplifiedUser};
 use crate::mappers::notification::doc_to_domain;
+use crate::infrastructure::indexes::RequiredIndex;
+use crate::metrics::observe_mongo;
+
+const REPOSITORY: &str = "MongoNotificationRepository";
 
 #[derive(Clone)]
 pub struct MongoNotificationRepository {
@@ -15,6 +19,16 @@ pub struct MongoNotificationRepository {
 
 impl MongoNotificationRepository {
     pub fn new(db: Database) -> Self { Self { db } }
+
+    /// Índices que necesitan las consultas (ver `IndexManager`)
+    pub fn required_indexes() -> Vec<RequiredIndex> {
+        vec![
+            RequiredIndex::new("Notification", doc! { "businessId": 1, "creationDate": -1, "deleted": 1, "type": 1 }),
+            RequiredIndex::new("Notification", doc! { "topic": 1 }),
+            RequiredIndex::new("Notification", doc! { "userTargets": 1 }),
+            RequiredIndex::new("Notification", doc! { "phones": 1 }),
+        ]
+    }
 }
 
 #[async_trait]
@@ -28,8 +42,7 @@ impl NotificationRepository for MongoNotificationRepository {
             "deleted": false 
         };
         let coll = self.db.collection::<Document>("Notification");
-        let doc = match coll
-            .find_one(filter)
+        let doc = match observe_mongo(REPOSITORY, "find_by_id", coll.find_one(filter))
             .await
             .map_err(|e| NotificationRepoError::Unexpected(e.to_string()))? {
             Some(d) => d,
@@ -47,7 +60,7 @@ impl NotificationRepository for MongoNotificationRepository {
         // Convertir businessIds de String a ObjectId
         let business_oids: Result<Vec<ObjectId>, _> = business_ids
             .iter()
-            .map(|bid| ObjectId::parse_str(bid))
+            .map(ObjectId::parse_str)
             .collect();
         let business_oids = business_oids.map_err(|e| NotificationRepoError::Unexpected(e.to_string()))?;
         
@@ -122,11 +135,7 @@ impl NotificationRepository for MongoNotificationRepository {
         };
 
         // Optimización: limitar resultados y usar batch size óptimo
-        // IMPORTANTE: Para máximo rendimiento, crear índices en MongoDB:
-        // db.Notification.createIndex({ "businessId": 1, "creationDate": -1, "deleted": 1, "type": 1 })
-        // db.Notification.createIndex({ "topic": 1 })
-        // db.Notification.createIndex({ "userTargets": 1 })
-        // db.Notification.createIndex({ "phones": 1 })
+        // Índices declarados en `required_indexes`
         let options = FindOptions::builder()
             .projection(doc! { "_id": 1 })
             .limit(1000) // Límite razonable para obtener unread count preciso
@@ -136,14 +145,9 @@ impl NotificationRepository for MongoNotificationRepository {
         let coll = self.db.collection::<Document>("Notification");
         
         // Optimización: usar collect en lugar de iterar cursor para mejor rendimiento
-        let cursor = coll
-            .find(filter)
-            .with_options(options)
-            .await
-            .map_err(|e| NotificationRepoError::Unexpected(e.to_string()))?;
-        
-        let docs: Vec<Document> = cursor
-            .try_collect::<Vec<_>>()
+        let docs: Vec<Document> = observe_mongo(REPOSITORY, "find_users_notifications", async {
+            coll.find(filter).with_options(options).await?.try_collect::<Vec<_>>().await
+        })
             .await
             .map_err(|e| NotificationRepoError::Unexpected(e.to_string()))?;
 
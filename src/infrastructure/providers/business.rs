@@ -1,19 +1,23 @@
-use crate::application::GetBusinessUseCase;
+use crate::application::GetTenantConfigUseCase;
+use crate::config::ConfigHandle;
+use crate::infrastructure::business::cached::CachedBusinessRepository;
 use crate::infrastructure::business::mongo::MongoBusinessRepository;
 use crate::infrastructure::db::Databases;
 
 #[derive(Clone)]
 pub struct BusinessServiceProvider {
-    pub get_business: GetBusinessUseCase<MongoBusinessRepository>,
+    pub get_tenant_config: GetTenantConfigUseCase<CachedBusinessRepository<MongoBusinessRepository>>,
 }
 
 impl BusinessServiceProvider {
-    pub fn new(databases: &Databases) -> Self {
-        let business_repo = MongoBusinessRepository::new(databases.client_db.clone());
+    pub fn new(databases: &Databases, config: &ConfigHandle) -> Self {
+        let business_repo = CachedBusinessRepository::new(
+            MongoBusinessRepository::new(databases.client_db.clone()),
+            config.clone(),
+        );
 
         Self {
-            get_business: GetBusinessUseCase::new(business_repo),
+            get_tenant_config: GetTenantConfigUseCase::new(business_repo, config.clone()),
         }
     }
 }
-
@@ -0,0 +1,27 @@
+use crate::application::{IsFeatureEnabledUseCase, ListFeatureFlagsUseCase, ToggleFeatureFlagUseCase};
+use crate::config::ConfigHandle;
+use crate::infrastructure::db::Databases;
+use crate::infrastructure::feature_flag::cached::CachedFeatureFlagRepository;
+use crate::infrastructure::feature_flag::mongo::MongoFeatureFlagRepository;
+
+#[derive(Clone)]
+pub struct FeatureFlagServiceProvider {
+    pub is_enabled: IsFeatureEnabledUseCase<CachedFeatureFlagRepository<MongoFeatureFlagRepository>>,
+    /// Lee siempre de Mongo: la API admin muestra el estado real, no el cacheado
+    pub list: ListFeatureFlagsUseCase<MongoFeatureFlagRepository>,
+    /// Pasa por la caché para invalidarla en esta instancia
+    pub toggle: ToggleFeatureFlagUseCase<CachedFeatureFlagRepository<MongoFeatureFlagRepository>>,
+}
+
+impl FeatureFlagServiceProvider {
+    pub fn new(databases: &Databases, config: &ConfigHandle) -> Self {
+        let flag_repo = MongoFeatureFlagRepository::new(databases.notifications_db.clone());
+        let cached_repo = CachedFeatureFlagRepository::new(flag_repo.clone(), config.clone());
+
+        Self {
+            is_enabled: IsFeatureEnabledUseCase::new(cached_repo.clone()),
+            list: ListFeatureFlagsUseCase::new(flag_repo),
+            toggle: ToggleFeatureFlagUseCase::new(cached_repo),
+        }
+    }
+}
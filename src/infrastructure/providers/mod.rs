@@ -4,6 +4,7 @@ pub mod session;
 pub mod business;
 pub mod analytics;
 pub mod storage;
+pub mod feature_flag;
 
 pub use notification::NotificationServiceProvider;
 pub use user::UserServiceProvider;
@@ -11,4 +12,4 @@ pub use session::SessionServiceProvider;
 pub use business::BusinessServiceProvider;
 pub use analytics::AnalyticsServiceProvider;
 pub use storage::StorageServiceProvider;
-
+pub use feature_flag::FeatureFlagServiceProvider;
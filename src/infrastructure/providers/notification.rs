@@ -1,6 +1,7 @@
-use crate::application::notification::{GetNotificationUseCase, GetUsersNotificationsUseCase, GetGetStreamMessageUseCase, GetGetStreamUnreadCountUseCase, EnqueueTrackNotificationUseCase};
+use crate::application::notification::{GetNotificationUseCase, GetUsersNotificationsUseCase, GetGetStreamMessageUseCase, GetGetStreamUnreadCountUseCase, EnqueueTrackNotificationUseCase, EnqueueNotificationEventUseCase};
 use crate::infrastructure::notification::mongo::MongoNotificationRepository;
 use crate::infrastructure::external::{getstream::HttpGetStreamRepository, queue::QueueService};
+use crate::config::ConfigHandle;
 use crate::infrastructure::db::Databases;
 
 #[derive(Clone)]
@@ -10,14 +11,16 @@ pub struct NotificationServiceProvider {
     pub get_getstream_message: GetGetStreamMessageUseCase<HttpGetStreamRepository>,
     pub get_getstream_unread_count: GetGetStreamUnreadCountUseCase<HttpGetStreamRepository>,
     pub enqueue_track_notification: EnqueueTrackNotificationUseCase,
+    pub enqueue_notification_event: EnqueueNotificationEventUseCase,
 }
 
 impl NotificationServiceProvider {
-    pub fn new(databases: &Databases) -> Self {
+    pub fn new(databases: &Databases, config: &ConfigHandle) -> Self {
         let notification_repo = MongoNotificationRepository::new(databases.notifications_db.clone());
-        let external_repo = HttpGetStreamRepository::default();
-        let queue_service = QueueService::new();
-        let enqueue_track = EnqueueTrackNotificationUseCase::new(queue_service);
+        let external_repo = HttpGetStreamRepository::new(config.clone());
+        let queue_service = QueueService::new(config.clone());
+        let enqueue_track = EnqueueTrackNotificationUseCase::new(queue_service.clone());
+        let enqueue_event = EnqueueNotificationEventUseCase::new(queue_service);
 
         Self {
             get_notification: GetNotificationUseCase::new(notification_repo.clone()),
@@ -25,6 +28,7 @@ impl NotificationServiceProvider {
             get_getstream_message: GetGetStreamMessageUseCase::new(external_repo.clone()),
             get_getstream_unread_count: GetGetStreamUnreadCountUseCase::new(external_repo),
             enqueue_track_notification: enqueue_track,
+            enqueue_notification_event: enqueue_event,
         }
     }
 }
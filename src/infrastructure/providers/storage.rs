@@ -1,17 +1,263 @@
+use futures::future::join_all;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use crate::config::{AppConfig, ConfigHandle, ImageExistenceCheck, StorageBackend, UrlStrategy};
+use crate::domain::TenantConfig;
+use crate::infrastructure::cloudfront::CloudFrontUrlSigner;
+use crate::infrastructure::image_variants::ImageVariantResolver;
+use crate::infrastructure::key_rules::{KeyResolution, KeyRewriter};
 use crate::infrastructure::s3::S3UrlSigner;
+use crate::infrastructure::storage::{ImageUrlSigner, StaticUrlSigner, StorageObject};
+use crate::types::ImageHint;
+
+/// Imágenes de notificaciones cuya key no existe en el almacenamiento
+pub static MISSING_IMAGES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "notification_image_missing_total",
+        "Image keys referenced by notifications that do not exist in storage",
+        &["business_id"]
+    )
+    .expect("metric registered twice")
+});
+
+/// Estrategia para construir las URLs de las imágenes de notificaciones
+#[derive(Clone)]
+pub enum StorageUrlStrategy {
+    /// URL prefirmada por el backend de almacenamiento (S3, MinIO o servidor local)
+    S3Presign,
+    /// URL firmada de CloudFront (canned policy con key pair ID y clave privada RSA)
+    CloudFrontSigned(CloudFrontUrlSigner),
+    /// URL pública del CDN: `{base_url}/{key}` sin firma
+    PublicCdn(StaticUrlSigner),
+}
+
+/// URL de una imagen de la notificación
+#[derive(Clone, Debug)]
+pub struct ImageUrl {
+    pub url: String,
+    /// La key no existe en el almacenamiento (solo con `ImageExistenceCheck::Mark`)
+    pub missing: bool,
+}
 
 #[derive(Clone)]
 pub struct StorageServiceProvider {
-    pub s3_signer: S3UrlSigner,
+    /// Backend de almacenamiento seleccionado con `storage.backend`
+    pub signer: Arc<dyn ImageUrlSigner>,
+    default_strategy: StorageUrlStrategy,
+    business_strategies: Arc<HashMap<String, StorageUrlStrategy>>,
+    variants: ImageVariantResolver,
+    key_rewriter: Arc<KeyRewriter>,
+    existence_check: ImageExistenceCheck,
 }
 
 impl StorageServiceProvider {
-    pub async fn new() -> Result<Self, String> {
-        let s3_signer = S3UrlSigner::new().await?;
+    /// Crea el provider con el backend de almacenamiento, la estrategia por defecto y las de cada business
+    /// según la sección `[storage]` (y `[cloudfront]` si alguna estrategia es "cloudfront")
+    pub async fn new(config: &ConfigHandle) -> Result<Self, String> {
+        let current = config.current();
+        let storage = &current.storage;
+
+        // Las reglas se validan antes que nada: una configuración inválida impide arrancar
+        let key_rewriter = KeyRewriter::from_config(storage)?;
+        let existence_check = storage.existence_check;
+
+        let backend = storage.backend;
+        let signer = Self::build_backend(backend, config).await?;
+
+        // El firmador de CloudFront se crea una sola vez y se comparte entre businesses (y su caché)
+        let mut builder = StrategyBuilder::new(&current);
+        let default_strategy = builder.build(storage.url_strategy)?;
+
+        let mut business_strategies = HashMap::new();
+        for (business_id, strategy) in &storage.url_strategy_by_business {
+            business_strategies.insert(business_id.clone(), builder.build(*strategy)?);
+        }
+
+        tracing::info!(
+            backend = ?backend,
+            strategy = ?storage.url_strategy,
+            business_overrides = business_strategies.len(),
+            key_rules = key_rewriter.rule_count(),
+            existence_check = ?existence_check,
+            "Storage service configured"
+        );
+        if existence_check != ImageExistenceCheck::Off && !signer.can_check_existence() {
+            tracing::warn!(backend = ?backend, "Storage backend cannot check object existence, storage.existence_check ignored");
+        }
 
         Ok(Self {
-            s3_signer,
+            signer,
+            default_strategy,
+            business_strategies: Arc::new(business_strategies),
+            variants: ImageVariantResolver::from_config(&storage.variants),
+            key_rewriter: Arc::new(key_rewriter),
+            existence_check,
         })
     }
+
+    /// Crea el backend de almacenamiento
+    /// "local" no necesita credenciales: devuelve `{storage.local_base_url}/{key}` (servidor estático o MinIO público)
+    /// y, si se define `storage.local_dir`, comprueba en ese directorio qué variantes de imagen existen
+    async fn build_backend(backend: StorageBackend, config: &ConfigHandle) -> Result<Arc<dyn ImageUrlSigner>, String> {
+        match backend {
+            StorageBackend::S3 => Ok(Arc::new(S3UrlSigner::new(config).await?)),
+            StorageBackend::Minio => Ok(Arc::new(S3UrlSigner::custom_endpoint(config).await?)),
+            StorageBackend::Local => {
+                let storage = &config.current().storage;
+                let signer = match &storage.local_dir {
+                    Some(dir) => StaticUrlSigner::new(&storage.local_base_url).with_root_dir(dir),
+                    None => StaticUrlSigner::new(&storage.local_base_url),
+                };
+                Ok(Arc::new(signer))
+            }
+        }
+    }
+
+    /// Estrategia configurada para el business (o la estrategia por defecto)
+    pub fn strategy_for(&self, business_id: Option<&str>) -> &StorageUrlStrategy {
+        business_id
+            .and_then(|id| self.business_strategies.get(id))
+            .unwrap_or(&self.default_strategy)
+    }
+
+    /// Resuelve el path guardado en la notificación con las reglas de reescritura (sin firmar)
+    pub fn resolve_key(&self, business_id: Option<&str>, path: &str) -> KeyResolution {
+        self.key_rewriter.resolve(business_id, path)
+    }
+
+    /// Resuelve el path como `image_urls`: reglas de reescritura y, si no indican bucket, el del tenant
+    pub fn resolve_for_tenant(&self, tenant: Option<&TenantConfig>, path: &str) -> KeyResolution {
+        let mut resolution = self.resolve_key(tenant.map(|t| t.business_id.as_str()), path);
+        if resolution.object.bucket.is_none() {
+            resolution.object.bucket = tenant.and_then(|t| t.bucket.clone());
+        }
+        resolution
+    }
+
+    /// CDN del tenant con el que se construyen sus URLs, salvo que `storage.url_strategy_by_business`
+    /// fije una estrategia para el business
+    pub fn tenant_cdn<'a>(&self, tenant: Option<&'a TenantConfig>) -> Option<&'a str> {
+        tenant
+            .filter(|t| !self.business_strategies.contains_key(&t.business_id))
+            .and_then(|t| t.cdn_base_url.as_deref())
+    }
+
+    /// Construye las URLs de las imágenes según la estrategia del business
+    /// Con pista del cliente se usa la variante pregenerada (@2x, miniatura...) si existe, o el original.
+    /// Con `storage.existence_check` las keys inexistentes se descartan o se marcan (y se cuentan en métricas).
+    /// El tenant aporta su bucket (si las reglas de keys no indican otro) y su CDN (si
+    /// `storage.url_strategy_by_business` no fija una estrategia para el business)
+    pub async fn image_urls(
+        &self,
+        tenant: Option<&TenantConfig>,
+        paths: &[String],
+        hint: &ImageHint,
+        expires_in: u64,
+    ) -> Result<Vec<ImageUrl>, String> {
+        let business_id = tenant.map(|t| t.business_id.as_str());
+        let objects: Vec<StorageObject> = paths
+            .iter()
+            .map(|path| self.resolve_for_tenant(tenant, path).object)
+            .collect();
+        let missing = self.missing_objects(business_id, &objects).await;
+
+        let objects: Vec<StorageObject> = if self.existence_check == ImageExistenceCheck::Drop {
+            objects.into_iter().zip(&missing).filter(|(_, missing)| !**missing).map(|(o, _)| o).collect()
+        } else {
+            objects
+        };
+        let objects = self.variants.resolve(self.signer.as_ref(), &objects, hint).await;
+        let objects = objects.as_slice();
+
+        if let Some(cdn_base_url) = self.tenant_cdn(tenant) {
+            let urls = StaticUrlSigner::new(cdn_base_url).sign_urls(objects, expires_in).await?;
+            return Ok(self.mark_missing(urls, &missing));
+        }
+
+        let urls = match self.strategy_for(business_id) {
+            StorageUrlStrategy::S3Presign => self.signer.sign_urls(objects, expires_in).await?,
+            StorageUrlStrategy::CloudFrontSigned(signer) => signer.sign_urls(objects, expires_in).await?,
+            StorageUrlStrategy::PublicCdn(signer) => signer.sign_urls(objects, expires_in).await?,
+        };
+
+        Ok(self.mark_missing(urls, &missing))
+    }
+
+    fn mark_missing(&self, urls: Vec<String>, missing: &[bool]) -> Vec<ImageUrl> {
+        let marked = self.existence_check == ImageExistenceCheck::Mark;
+        urls.into_iter()
+            .enumerate()
+            .map(|(i, url)| ImageUrl { url, missing: marked && missing[i] })
+            .collect()
+    }
+
+    /// Comprueba en paralelo qué objetos no existen (todos existen si la comprobación está desactivada)
+    /// Ante errores del almacenamiento se asume que existe, para no ocultar imágenes válidas
+    async fn missing_objects(&self, business_id: Option<&str>, objects: &[StorageObject]) -> Vec<bool> {
+        if self.existence_check == ImageExistenceCheck::Off || !self.signer.can_check_existence() {
+            return vec![false; objects.len()];
+        }
+
+        join_all(objects.iter().map(|object| async move {
+            match self.signer.object_exists(object).await {
+                Ok(true) => false,
+                Ok(false) => {
+                    MISSING_IMAGES
+                        .with_label_values(&[business_id.unwrap_or("unknown")])
+                        .inc();
+                    tracing::warn!(
+                        business_id = business_id.unwrap_or("unknown"),
+                        bucket = object.bucket.as_deref().unwrap_or("default"),
+                        key = %object.key,
+                        "Missing image"
+                    );
+                    true
+                }
+                Err(e) => {
+                    tracing::warn!(key = %object.key, error = %e, "Could not check image existence");
+                    false
+                }
+            }
+        }))
+        .await
+    }
+}
+
+/// Construye estrategias de la configuración, reutilizando el firmador de CloudFront
+struct StrategyBuilder<'a> {
+    config: &'a AppConfig,
+    cloudfront: Option<CloudFrontUrlSigner>,
 }
 
+impl<'a> StrategyBuilder<'a> {
+    fn new(config: &'a AppConfig) -> Self {
+        Self { config, cloudfront: None }
+    }
+
+    fn build(&mut self, strategy: UrlStrategy) -> Result<StorageUrlStrategy, String> {
+        match strategy {
+            UrlStrategy::S3 => Ok(StorageUrlStrategy::S3Presign),
+            UrlStrategy::Cloudfront => {
+                let signer = match &self.cloudfront {
+                    Some(signer) => signer.clone(),
+                    None => {
+                        let signer = CloudFrontUrlSigner::from_config(
+                            &self.config.cloudfront,
+                            &self.config.storage.url_cache,
+                        )?;
+                        self.cloudfront = Some(signer.clone());
+                        signer
+                    }
+                };
+                Ok(StorageUrlStrategy::CloudFrontSigned(signer))
+            }
+            UrlStrategy::Cdn => {
+                let base_url = self.config.storage.public_cdn_base_url.as_deref()
+                    .ok_or("storage.public_cdn_base_url is required by the cdn strategy")?;
+                Ok(StorageUrlStrategy::PublicCdn(StaticUrlSigner::new(base_url)))
+            }
+        }
+    }
+}
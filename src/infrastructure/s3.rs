@@ -3,111 +3,195 @@ use aws_sdk_s3::Client as S3Client;
 use aws_sdk_s3::presigning::PresigningConfig;
 use std::time::Duration;
 use std::sync::Arc;
+use async_trait::async_trait;
+use aws_credential_types::Credentials;
+use aws_credential_types::provider::{future, ProvideCredentials};
 
-/// Servicio para firmar URLs de S3
+use crate::config::{ConfigHandle, StorageConfig};
+
+use crate::infrastructure::existence_cache::ExistenceCache;
+use crate::infrastructure::signed_url_cache::SignedUrlCache;
+use crate::infrastructure::storage::{is_absolute_url, ImageUrlSigner, StorageObject};
+
+/// Servicio para firmar URLs de S3 (AWS o un endpoint compatible, como MinIO)
 #[derive(Clone)]
 pub struct S3UrlSigner {
     client: Arc<S3Client>,
     bucket_name: String,
+    cache: Arc<SignedUrlCache>,
+    existence: Arc<ExistenceCache>,
+    /// Con credenciales estáticas: se leen de la configuración en cada firma (rotación sin reiniciar)
+    credentials: Option<ConfigHandle>,
+}
+
+/// Credenciales de `AppConfig::s3` en uso; sin caché para que una recarga se aplique a la siguiente firma
+#[derive(Debug)]
+struct ConfigCredentials(ConfigHandle);
+
+impl ProvideCredentials for ConfigCredentials {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        let config = self.0.current();
+        future::ProvideCredentials::ready(Ok(Credentials::new(
+            config.s3.access_key.expose(),
+            config.s3.secret_key.expose(),
+            None,
+            None,
+            "app-config",
+        )))
+    }
 }
 
 impl S3UrlSigner {
-    /// Crea una nueva instancia del firmador de URLs de S3
-    pub async fn new() -> Result<Self, String> {
-        let bucket_name = std::env::var("PUBLIC_BUCKET")
-            .map_err(|_| "PUBLIC_BUCKET environment variable not set")?;
-
-        let region_str = std::env::var("AWS_REGION")
-            .unwrap_or_else(|_| "eu-west-3".to_string());
-        let region = Region::new(region_str);
-
-        // Configurar credenciales desde variables de entorno
-        // Temporalmente establecer las variables con nombres estándar de AWS
-        let access_key = std::env::var("AWS_ACCESS_KEY")
-            .map_err(|_| "AWS_ACCESS_KEY environment variable not set")?;
-        let secret_key = std::env::var("AWS_SECRET_KEY")
-            .map_err(|_| "AWS_SECRET_KEY environment variable not set")?;
-
-        // Establecer las variables con los nombres estándar que aws-config espera
-        std::env::set_var("AWS_ACCESS_KEY_ID", &access_key);
-        std::env::set_var("AWS_SECRET_ACCESS_KEY", &secret_key);
-
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(region)
+    /// Crea el firmador de URLs para AWS S3 con el bucket de `storage.bucket` y la región de `s3.region`
+    /// Credenciales: `s3.access_key`/`s3.secret_key` de la configuración (`AWS_ACCESS_KEY`/`AWS_SECRET_KEY`)
+    /// si existen, recargables; si no, la cadena por defecto de AWS (variables estándar, perfil, rol de instancia...)
+    pub async fn new(config: &ConfigHandle) -> Result<Self, String> {
+        let current = config.current();
+        let region = current.s3.region.clone().unwrap_or_else(|| "eu-west-3".to_string());
+
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(Region::new(region));
+        let static_credentials = current.s3.has_static_credentials();
+        if static_credentials {
+            loader = loader
+                .credentials_provider(ConfigCredentials(config.clone()))
+                .identity_cache(aws_config::identity::IdentityCache::no_cache());
+        }
+        let sdk_config = loader.load().await;
+
+        let mut signer = Self::with_client(S3Client::new(&sdk_config), &current.storage);
+        signer.credentials = static_credentials.then(|| config.clone());
+        Ok(signer)
+    }
+
+    /// Crea el firmador para un almacenamiento compatible con S3 en un endpoint propio (ej: MinIO local)
+    /// con `s3.endpoint_url` y las credenciales estáticas de `s3` (obligatorias, se validan al cargar la configuración)
+    pub async fn custom_endpoint(config: &ConfigHandle) -> Result<Self, String> {
+        let current = config.current();
+        let endpoint_url = current.s3.endpoint_url.clone()
+            .ok_or("s3.endpoint_url is required by the minio backend")?;
+        let region = current.s3.region.clone().unwrap_or_else(|| "us-east-1".to_string());
+
+        let sdk_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(Region::new(region))
+            .credentials_provider(ConfigCredentials(config.clone()))
+            .identity_cache(aws_config::identity::IdentityCache::no_cache())
             .load()
             .await;
 
-        let client = Arc::new(S3Client::new(&config));
+        // MinIO y la mayoría de servicios compatibles no soportan virtual-hosted style (bucket.host)
+        let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .endpoint_url(endpoint_url)
+            .force_path_style(true)
+            .build();
 
-        Ok(Self {
-            client,
-            bucket_name,
-        })
+        let mut signer = Self::with_client(S3Client::from_conf(s3_config), &current.storage);
+        signer.credentials = Some(config.clone());
+        Ok(signer)
     }
 
-    /// Normaliza el path de la imagen para que coincida con la estructura en S3
-    /// Convierte "notification/image/..." a "notifications/images/..."
-    fn normalize_key(&self, key: &str) -> String {
-        // Si la key ya es una URL completa, retornarla sin modificar
-        if key.starts_with("http://") || key.starts_with("https://") {
-            return key.to_string();
+    fn with_client(client: S3Client, storage: &StorageConfig) -> Self {
+        Self {
+            client: Arc::new(client),
+            bucket_name: storage.bucket.clone(),
+            cache: Arc::new(SignedUrlCache::from_config(&storage.url_cache)),
+            existence: Arc::new(ExistenceCache::from_config(&storage.existence_cache)),
+            credentials: None,
         }
+    }
 
-        // Transformar "notification/image/" a "notifications/images/"
-        let normalized = if key.starts_with("notification/image/") {
-            format!("notifications/images/{}", &key[19..]) // "notification/image/".len() = 19
-        } else if key.starts_with("notification/images/") {
-            format!("notifications/images/{}", &key[20..]) // "notification/images/".len() = 20
-        } else if key.starts_with("notifications/image/") {
-            format!("notifications/images/{}", &key[21..]) // "notifications/image/".len() = 21
-        } else {
-            key.to_string() // Ya está bien o tiene otro formato
-        };
-
-        normalized
+    /// Bucket del objeto: el específico del business o el bucket por defecto (`storage.bucket`)
+    fn bucket_for<'a>(&'a self, object: &'a StorageObject) -> &'a str {
+        object.bucket.as_deref().unwrap_or(&self.bucket_name)
     }
 
     /// Firma una URL de S3 para un objeto específico
     /// Equivalente a getSignedUrl con GetObjectCommand en TypeScript
     /// 
     /// # Argumentos
-    /// * `key` - La clave del objeto en S3 (ej: "notifications/images/image.png" o "notification/image/image.png")
+    /// * `object` - Key ya resuelta por `KeyRewriter` (ej: "notifications/images/image.png") y bucket opcional
     /// * `expires_in` - Duración en segundos para la validez de la URL (default: 600 = 10 minutos)
     /// 
     /// # Retorna
-    /// Una URL firmada de S3 o un error
-    pub async fn sign_url(&self, key: &str, expires_in: u64) -> Result<String, String> {
-        // Normalizar la key antes de firmarla
-        let normalized_key = self.normalize_key(key);
+    /// Una URL firmada de S3 (reutilizada de la caché si sigue siendo válida) o un error
+    async fn presign(&self, object: &StorageObject, expires_in: u64) -> Result<String, String> {
+        // Las URLs completas no se firman
+        if is_absolute_url(&object.key) {
+            return Ok(object.key.clone());
+        }
+
+        let bucket = self.bucket_for(object);
+        // La misma key puede existir en varios buckets: la caché se indexa por ambos
+        // (y por la access key: tras rotarla no se reutilizan URLs firmadas con la anterior)
+        let cache_key = match &self.credentials {
+            Some(config) => format!("{}:{}/{}", config.current().s3.access_key.expose(), bucket, object.key),
+            None => format!("{}/{}", bucket, object.key),
+        };
+        if let Some(url) = self.cache.get(&cache_key, expires_in) {
+            return Ok(url);
+        }
 
         // Construir la configuración de presigning (equivalente a { expiresIn: params.Expires } en TS)
         let presigning_config = PresigningConfig::expires_in(Duration::from_secs(expires_in))
             .map_err(|e| format!("Error creating presigning config: {}", e))?;
 
         // Crear el request de presigned URL (equivalente a new GetObjectCommand en TS)
+        let start = std::time::Instant::now();
         let request = self
             .client
             .get_object()
-            .bucket(&self.bucket_name)
-            .key(&normalized_key)
+            .bucket(bucket)
+            .key(&object.key)
             .presigned(presigning_config)
             .await
             .map_err(|e| format!("Error generating presigned URL for bucket '{}', key '{}': {}", 
-                                 self.bucket_name, normalized_key, e))?;
+                                 bucket, object.key, e))?;
+
+        crate::metrics::S3_SIGN_DURATION
+            .with_label_values(&[bucket])
+            .observe(start.elapsed().as_secs_f64());
+
+        let url = request.uri().to_string();
+        self.cache.insert(&cache_key, expires_in, url.clone());
+
+        Ok(url)
+    }
+}
 
-        Ok(request.uri().to_string())
+/// Las URLs en caché se devuelven al momento; con `sign_urls` el resto se firman en paralelo
+#[async_trait]
+impl ImageUrlSigner for S3UrlSigner {
+    async fn sign_url(&self, object: &StorageObject, expires_in: u64) -> Result<String, String> {
+        self.presign(object, expires_in).await
     }
 
-    /// Firma múltiples URLs de S3
-    pub async fn sign_urls(&self, keys: &[String], expires_in: u64) -> Result<Vec<String>, String> {
-        let mut signed_urls = Vec::new();
+    fn can_check_existence(&self) -> bool {
+        true
+    }
 
-        for key in keys {
-            let signed_url = self.sign_url(key, expires_in).await?;
-            signed_urls.push(signed_url);
+    /// Comprueba si el objeto existe con HeadObject
+    /// El resultado (positivo o negativo) se cachea; los errores no, para reintentar en el siguiente request
+    async fn object_exists(&self, object: &StorageObject) -> Result<bool, String> {
+        if is_absolute_url(&object.key) {
+            return Ok(true);
         }
+        if let Some(exists) = self.existence.get(object) {
+            return Ok(exists);
+        }
+
+        let bucket = self.bucket_for(object);
+        let exists = match self.client.head_object().bucket(bucket).key(&object.key).send().await {
+            Ok(_) => true,
+            Err(e) if e.as_service_error().is_some_and(|se| se.is_not_found()) => false,
+            Err(e) => return Err(format!("Error checking object '{}' in bucket '{}': {}",
+                                         object.key, bucket, e)),
+        };
+        self.existence.insert(object, exists);
 
-        Ok(signed_urls)
+        Ok(exists)
     }
 }
 
@@ -119,9 +203,10 @@ mod tests {
     #[ignore] // Ignorar en CI/CD ya que requiere credenciales AWS
     async fn test_sign_url() {
         // Este test requiere variables de entorno configuradas
-        if let Ok(signer) = S3UrlSigner::new().await {
-            let key = "notifications/images/test.png";
-            let result = signer.sign_url(key, 600).await;
+        let config = ConfigHandle::new(crate::config::AppConfig::load(None).unwrap_or_default(), None);
+        if let Ok(signer) = S3UrlSigner::new(&config).await {
+            let object = StorageObject { key: "notifications/images/test.png".to_string(), bucket: None };
+            let result = signer.sign_url(&object, 600).await;
             assert!(result.is_ok());
             let url = result.unwrap();
             assert!(url.contains("https://"));
@@ -5,8 +5,11 @@ use crate::infrastructure::providers::{
     BusinessServiceProvider,
     AnalyticsServiceProvider,
     StorageServiceProvider,
+    FeatureFlagServiceProvider,
 };
+use crate::config::ConfigHandle;
 use crate::infrastructure::db::Databases;
+use crate::infrastructure::health::ReadinessChecker;
 
 /// Contenedor centralizado de todos los servicios de la aplicación
 /// Organiza los servicios por categoría usando service providers
@@ -18,21 +21,29 @@ pub struct AppServices {
     pub business: BusinessServiceProvider,
     pub analytics: AnalyticsServiceProvider,
     pub storage: StorageServiceProvider,
+    /// Feature flags por business, tipo de cuenta y porcentaje de usuarios
+    pub feature_flags: FeatureFlagServiceProvider,
+    /// Comprobación de dependencias para `/health/ready`
+    pub readiness: ReadinessChecker,
+    /// Configuración validada al arrancar y recargable en caliente (`ConfigHandle::watch`)
+    pub config: ConfigHandle,
 }
 
 impl AppServices {
     /// Crea todos los servicios a partir de las bases de datos usando service providers
-    pub async fn new(databases: &Databases) -> Result<Self, String> {
-        eprintln!("[AppServices] Initializing all service providers...");
+    pub async fn new(databases: &Databases, config: ConfigHandle) -> Result<Self, String> {
+        tracing::info!("Initializing service providers");
         
-        let notification_provider = NotificationServiceProvider::new(databases);
+        let notification_provider = NotificationServiceProvider::new(databases, &config);
         let user_provider = UserServiceProvider::new(databases);
         let session_provider = SessionServiceProvider::new(databases);
-        let business_provider = BusinessServiceProvider::new(databases);
+        let business_provider = BusinessServiceProvider::new(databases, &config);
         let analytics_provider = AnalyticsServiceProvider::new(databases);
-        let storage_provider = StorageServiceProvider::new().await?;
+        let storage_provider = StorageServiceProvider::new(&config).await?;
+        let feature_flag_provider = FeatureFlagServiceProvider::new(databases, &config);
+        let readiness = ReadinessChecker::new(databases, storage_provider.clone(), &config);
 
-        eprintln!("[AppServices] All service providers initialized successfully");
+        tracing::info!("Service providers initialized");
 
         Ok(Self {
             notification: notification_provider,
@@ -41,6 +52,9 @@ impl AppServices {
             business: business_provider,
             analytics: analytics_provider,
             storage: storage_provider,
+            feature_flags: feature_flag_provider,
+            readiness,
+            config,
         })
     }
 }
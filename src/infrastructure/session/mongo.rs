@@ -4,6 +4,8 @@ use mongodb::bson::{doc, oid::ObjectId, Document};
 
 use crate::domain::{Session, SessionRepository, SessionRepoError};
 use crate::mappers::session::doc_to_domain;
+use crate::infrastructure::indexes::RequiredIndex;
+use crate::metrics::observe_mongo;
 
 #[derive(Clone)]
 pub struct MongoSessionRepository {
@@ -12,6 +14,12 @@ pub struct MongoSessionRepository {
 
 impl MongoSessionRepository {
     pub fn new(db: Database) -> Self { Self { db } }
+
+    /// Índices que necesitan las consultas (ver `IndexManager`)
+    /// La sesión se valida en cada request autenticado
+    pub fn required_indexes() -> Vec<RequiredIndex> {
+        vec![RequiredIndex::new("AccountSessionInfo", doc! { "sessionId": 1, "businessId": 1 })]
+    }
 }
 
 #[async_trait]
@@ -27,8 +35,8 @@ impl SessionRepository for MongoSessionRepository {
         let find_options = mongodb::options::FindOneOptions::builder()
             .projection(projection)
             .build();
-        let doc = match coll.find_one(filter)
-            .with_options(find_options)
+        let query = coll.find_one(filter).with_options(find_options);
+        let doc = match observe_mongo("MongoSessionRepository", "find_by_id", query)
             .await.map_err(|e| SessionRepoError::Unexpected(e.to_string()))? {
             Some(d) => d,
             None => return Err(SessionRepoError::NotFound),
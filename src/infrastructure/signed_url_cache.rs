@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use crate::config::UrlCacheConfig;
+use crate::infrastructure::ttl_cache::TtlCache;
+
+/// Cache en memoria de URLs firmadas
+///
+/// Clave: (key normalizada, expiración solicitada en segundos). Una URL se reutiliza mientras le quede
+/// más de `min_remaining_ratio` de su vida útil, así las notificaciones más leídas no vuelven a firmarse
+/// y la URL es estable entre requests (la caché de imágenes del cliente acierta).
+pub struct SignedUrlCache {
+    entries: TtlCache<(String, u64), String>,
+    min_remaining_ratio: f64,
+}
+
+impl SignedUrlCache {
+    pub fn new(min_remaining_ratio: f64, max_entries: usize) -> Self {
+        Self {
+            entries: TtlCache::new(max_entries),
+            min_remaining_ratio: min_remaining_ratio.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Crea la caché con `storage.url_cache`
+    pub fn from_config(config: &UrlCacheConfig) -> Self {
+        Self::new(config.min_remaining, config.max_entries)
+    }
+
+    /// Devuelve la URL cacheada si todavía le queda suficiente vida útil
+    pub fn get(&self, key: &str, expires_in: u64) -> Option<String> {
+        self.get_at(key, expires_in, Instant::now())
+    }
+
+    /// Guarda una URL recién firmada con una vida útil de `expires_in` segundos
+    pub fn insert(&self, key: &str, expires_in: u64, url: String) {
+        self.insert_at(key, expires_in, url, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, expires_in: u64, now: Instant) -> Option<String> {
+        self.entries.get_at(&(key.to_string(), expires_in), now)
+    }
+
+    fn insert_at(&self, key: &str, expires_in: u64, url: String, now: Instant) {
+        // La entrada deja de reutilizarse cuando le queda `min_remaining_ratio` de vida útil
+        let reusable_for = Duration::from_secs(expires_in).mul_f64(1.0 - self.min_remaining_ratio);
+        self.entries.insert_at((key.to_string(), expires_in), url, reusable_for, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_url_while_enough_lifetime_remains() {
+        let cache = SignedUrlCache::new(0.5, 10);
+        let now = Instant::now();
+        cache.insert_at("notifications/images/a.png", 600, "signed-a".to_string(), now);
+
+        let early = now + Duration::from_secs(200);
+        assert_eq!(cache.get_at("notifications/images/a.png", 600, early).as_deref(), Some("signed-a"));
+
+        // Quedan menos de 300s (50% de 600s): hay que volver a firmar
+        let late = now + Duration::from_secs(301);
+        assert_eq!(cache.get_at("notifications/images/a.png", 600, late), None);
+    }
+
+    #[test]
+    fn entries_are_keyed_by_expiration() {
+        let cache = SignedUrlCache::new(0.5, 10);
+        let now = Instant::now();
+        cache.insert_at("notifications/images/a.png", 600, "signed-600".to_string(), now);
+
+        assert_eq!(cache.get_at("notifications/images/a.png", 3600, now), None);
+    }
+
+    #[test]
+    fn evicts_when_full() {
+        let cache = SignedUrlCache::new(0.5, 2);
+        let now = Instant::now();
+        cache.insert_at("a", 600, "a".to_string(), now);
+        cache.insert_at("b", 600, "b".to_string(), now);
+        cache.insert_at("c", 600, "c".to_string(), now);
+
+        assert_eq!(cache.get_at("c", 600, now).as_deref(), Some("c"));
+        assert!(cache.entries.len() <= 2);
+    }
+}
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use std::path::PathBuf;
+
+/// Objeto del almacenamiento ya resuelto por las reglas de reescritura (ver `KeyRewriter`)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StorageObject {
+    /// Key real del objeto (o URL completa, que se devuelve tal cual)
+    pub key: String,
+    /// Bucket específico del business; None = bucket por defecto del backend
+    pub bucket: Option<String>,
+}
+
+impl StorageObject {
+    /// El mismo bucket con otra key (ej: una variante de la imagen)
+    pub fn with_key(&self, key: impl Into<String>) -> Self {
+        Self { key: key.into(), bucket: self.bucket.clone() }
+    }
+}
+
+/// Indica si el path es ya una URL completa (no se firma ni se reescribe)
+pub fn is_absolute_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// Construye URLs de acceso a las imágenes guardadas en el almacenamiento
+/// Implementaciones: `S3UrlSigner` (AWS o endpoint compatible como MinIO), `CloudFrontUrlSigner`
+/// y `StaticUrlSigner` (CDN público o servidor estático local)
+#[async_trait]
+pub trait ImageUrlSigner: Send + Sync {
+    /// Devuelve la URL de un objeto con una validez de `expires_in` segundos
+    async fn sign_url(&self, object: &StorageObject, expires_in: u64) -> Result<String, String>;
+
+    /// Devuelve las URLs de varios objetos, en paralelo y en el mismo orden
+    async fn sign_urls(&self, objects: &[StorageObject], expires_in: u64) -> Result<Vec<String>, String> {
+        try_join_all(objects.iter().map(|object| self.sign_url(object, expires_in))).await
+    }
+
+    /// Indica si el backend puede comprobar la existencia de objetos (`object_exists`)
+    fn can_check_existence(&self) -> bool {
+        false
+    }
+
+    /// Indica si el objeto existe en el almacenamiento
+    /// Por defecto no se puede comprobar y se considera inexistente (se usa el original)
+    async fn object_exists(&self, _object: &StorageObject) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+/// URLs sin firma: `{base_url}/{key}`
+/// Sirve tanto para un CDN público como para un servidor estático local en desarrollo
+/// (el bucket del objeto se ignora: todo se sirve bajo `base_url`)
+#[derive(Clone)]
+pub struct StaticUrlSigner {
+    base_url: String,
+    /// Directorio local con los ficheros servidos en `base_url` (para comprobar si existen)
+    root_dir: Option<PathBuf>,
+}
+
+impl StaticUrlSigner {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            root_dir: None,
+        }
+    }
+
+    pub fn with_root_dir(mut self, root_dir: impl Into<PathBuf>) -> Self {
+        self.root_dir = Some(root_dir.into());
+        self
+    }
+}
+
+#[async_trait]
+impl ImageUrlSigner for StaticUrlSigner {
+    async fn sign_url(&self, object: &StorageObject, _expires_in: u64) -> Result<String, String> {
+        if is_absolute_url(&object.key) {
+            return Ok(object.key.clone());
+        }
+        Ok(format!("{}/{}", self.base_url, object.key.trim_start_matches('/')))
+    }
+
+    fn can_check_existence(&self) -> bool {
+        self.root_dir.is_some()
+    }
+
+    async fn object_exists(&self, object: &StorageObject) -> Result<bool, String> {
+        let Some(root_dir) = &self.root_dir else {
+            return Ok(false);
+        };
+        let path = root_dir.join(object.key.trim_start_matches('/'));
+        Ok(tokio::fs::try_exists(path).await.unwrap_or(false))
+    }
+}
@@ -0,0 +1,101 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Cache en memoria con caducidad por entrada y tamaño acotado
+///
+/// Cada entrada se guarda con el instante hasta el que es válida (el TTL se decide al insertar).
+/// Al llenarse se descartan primero las entradas caducadas y, si no basta, se vacía entera:
+/// más simple que un LRU y suficiente para cachés que se repueblan solas. Con `max_entries = 0`
+/// la caché queda desactivada.
+pub struct TtlCache<K, V> {
+    entries: RwLock<HashMap<K, (V, Instant)>>,
+    max_entries: usize,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(max_entries: usize) -> Self {
+        Self { entries: RwLock::new(HashMap::new()), max_entries }
+    }
+
+    /// Valor cacheado si no ha caducado
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Guarda un valor válido durante `ttl`
+    pub fn insert(&self, key: K, value: V, ttl: Duration) {
+        self.insert_at(key, value, ttl, Instant::now())
+    }
+
+    pub fn get_at<Q>(&self, key: &Q, now: Instant) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if self.max_entries == 0 {
+            return None;
+        }
+        let entries = self.entries.read().ok()?;
+        let (value, valid_until) = entries.get(key)?;
+        (now < *valid_until).then(|| value.clone())
+    }
+
+    pub fn insert_at(&self, key: K, value: V, ttl: Duration, now: Instant) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let Ok(mut entries) = self.entries.write() else {
+            return;
+        };
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (_, valid_until)| now < *valid_until);
+            if entries.len() >= self.max_entries {
+                entries.clear();
+            }
+        }
+        entries.insert(key, (value, now + ttl));
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.read().map(|entries| entries.len()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_entries_and_evicts_when_full() {
+        let cache = TtlCache::new(2);
+        let now = Instant::now();
+        cache.insert_at("a".to_string(), 1, Duration::from_secs(10), now);
+        cache.insert_at("b".to_string(), 2, Duration::from_secs(60), now);
+
+        let later = now + Duration::from_secs(30);
+        assert_eq!(cache.get_at("a", later), None);
+        assert_eq!(cache.get_at("b", later), Some(2));
+
+        // Llena: se descarta la caducada y la vigente se conserva
+        cache.insert_at("c".to_string(), 3, Duration::from_secs(60), later);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_at("b", later), Some(2));
+
+        // Llena sin caducadas: se vacía
+        cache.insert_at("d".to_string(), 4, Duration::from_secs(60), later);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get_at("d", later), Some(4));
+
+        let disabled = TtlCache::new(0);
+        disabled.insert_at("a".to_string(), 1, Duration::from_secs(10), now);
+        assert_eq!(disabled.get_at("a", now), None);
+    }
+}
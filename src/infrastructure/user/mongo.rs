@@ -5,6 +5,10 @@ use mongodb::options::{FindOneOptions, FindOptions};
 use futures::stream::TryStreamExt; // Necesario para try_collect()
 use crate::domain::{SimplifiedUser, UserRepository, UserRepoError};
 use crate::mappers::user::doc_to_simplified;
+use crate::infrastructure::indexes::RequiredIndex;
+use crate::metrics::observe_mongo;
+
+const REPOSITORY: &str = "MongoUserRepository";
 
 #[derive(Clone)]
 pub struct MongoUserRepository {
@@ -13,6 +17,14 @@ pub struct MongoUserRepository {
 
 impl MongoUserRepository {
     pub fn new(db: Database) -> Self { Self { db } }
+
+    /// Índices que necesitan las consultas (ver `IndexManager`)
+    pub fn required_indexes() -> Vec<RequiredIndex> {
+        vec![
+            // Forzado con `.hint()` en `find_by_phone_and_business_ids`: sin él la consulta falla
+            RequiredIndex::new("Account", doc! { "phone": 1, "businessId": 1 }).named("phone_businessId_idx"),
+        ]
+    }
 }
 
 #[async_trait]
@@ -24,9 +36,8 @@ impl UserRepository for MongoUserRepository {
             .projection(doc! { "_id": 1, "phone": 1, "creationDate": 1, "accountType": 1 })
             .build();
         let coll = self.db.collection::<Document>("Account");
-        let doc = match coll
-            .find_one(doc! { "_id": oid, "businessId": bid })
-            .with_options(options)
+        let query = coll.find_one(doc! { "_id": oid, "businessId": bid }).with_options(options);
+        let doc = match observe_mongo(REPOSITORY, "find_simplified_by_id", query)
             .await
             .map_err(|e| UserRepoError::Unexpected(e.to_string()))? {
             Some(d) => d,
@@ -41,7 +52,7 @@ impl UserRepository for MongoUserRepository {
         // Convertir businessIds de String a ObjectId
         let business_oids: Result<Vec<ObjectId>, _> = business_ids
             .iter()
-            .map(|bid| ObjectId::parse_str(bid))
+            .map(ObjectId::parse_str)
             .collect();
         let business_oids = business_oids.map_err(|e| UserRepoError::Unexpected(e.to_string()))?;
         
@@ -50,9 +61,8 @@ impl UserRepository for MongoUserRepository {
             .build();
         
         let coll = self.db.collection::<Document>("Account");
-        let doc = match coll
-            .find_one(doc! { "_id": oid, "businessId": { "$in": business_oids } })
-            .with_options(options)
+        let query = coll.find_one(doc! { "_id": oid, "businessId": { "$in": business_oids } }).with_options(options);
+        let doc = match observe_mongo(REPOSITORY, "find_by_id_and_business_ids", query)
             .await
             .map_err(|e| UserRepoError::Unexpected(e.to_string()))? {
             Some(d) => d,
@@ -70,7 +80,7 @@ impl UserRepository for MongoUserRepository {
         // Convertir businessIds de String a ObjectId
         let business_oids: Result<Vec<ObjectId>, _> = business_ids
             .iter()
-            .map(|bid| ObjectId::parse_str(bid))
+            .map(ObjectId::parse_str)
             .collect();
         let business_oids = business_oids.map_err(|e| UserRepoError::Unexpected(e.to_string()))?;
         
@@ -80,9 +90,8 @@ impl UserRepository for MongoUserRepository {
             "businessId": { "$in": business_oids }
         };
 
-        // IMPORTANTE: Para máximo rendimiento, crear índice en MongoDB:
-        // db.Account.createIndex({ "phone": 1, "businessId": 1 }, { name: "phone_businessId_idx" })
-        // 
+        // El hint requiere el índice { phone: 1, businessId: 1 } (declarado en `required_indexes`)
+        //
         // Optimizaciones aplicadas:
         // - batch_size mayor que limit para evitar round-trips extra
         // - limit razonable para evitar cargar demasiados documentos
@@ -97,14 +106,9 @@ impl UserRepository for MongoUserRepository {
         let coll = self.db.collection::<Document>("Account");
         
         // Optimización: usar collect en lugar de iterar cursor para mejor rendimiento
-        let cursor = coll
-            .find(filter)
-            .with_options(options)
-            .await
-            .map_err(|e| UserRepoError::Unexpected(e.to_string()))?;
-        
-        let docs: Vec<Document> = cursor
-            .try_collect::<Vec<_>>()
+        let docs: Vec<Document> = observe_mongo(REPOSITORY, "find_by_phone_and_business_ids", async {
+            coll.find(filter).with_options(options).await?.try_collect::<Vec<_>>().await
+        })
             .await
             .map_err(|e| UserRepoError::Unexpected(e.to_string()))?;
         
@@ -113,7 +117,7 @@ impl UserRepository for MongoUserRepository {
             match doc_to_simplified(result) {
                 Ok(user) => users.push(user),
                 Err(e) => {
-                    eprintln!("[MongoUserRepository::find_by_phone_and_business_ids] Error mapping document: {:?}", e);
+                    tracing::warn!(repository = REPOSITORY, operation = "find_by_phone_and_business_ids", error = ?e, "Skipping account document that could not be mapped");
                     // Continuamos con el siguiente documento en lugar de fallar
                 }
             }
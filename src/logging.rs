@@ -0,0 +1,227 @@
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::infrastructure::external::loki::{self, LokiShipper};
+use crate::infrastructure::log_file::FileLogSink;
+use crate::middleware::logging::LoggingConfig;
+
+/// Target de los eventos de tracing que contienen una línea de log de acceso ya formateada (campo `line`)
+pub const ACCESS_LOG_TARGET: &str = "access_log";
+
+/// Destinos de los logs: una única capa de tracing para los logs de la aplicación y los de acceso
+///
+/// - Loki recibe todos los eventos por el mismo `LokiShipper` (mismos labels, lotes, gzip y reintentos)
+/// - El fichero (para promtail) recibe solo los logs de acceso
+///
+/// Los logs de acceso se envían tal cual (formato compatible con Grafana); el resto de eventos se
+/// serializan con el mismo esquema (`name`, `hostname`, `pid`, `level`, `msg`, `time`, `v`) más sus
+/// campos y los de sus spans (ej: `request_id`, `business_id`).
+#[derive(Clone)]
+pub struct LogSinks {
+    pub loki: Option<LokiShipper>,
+    pub file: Option<FileLogSink>,
+    service_name: String,
+    hostname: String,
+}
+
+impl LogSinks {
+    pub fn new(config: &LoggingConfig, loki: Option<LokiShipper>, file: Option<FileLogSink>) -> Self {
+        Self {
+            loki,
+            file,
+            service_name: config.service_name.clone(),
+            hostname: config.hostname.clone(),
+        }
+    }
+
+    /// Envía a Loki y escribe en disco todo lo pendiente (apagado del servidor)
+    pub async fn flush(&self, timeout: std::time::Duration) {
+        if let Some(loki) = &self.loki {
+            if !loki.shutdown(timeout).await {
+                tracing::warn!(timeout_ms = timeout.as_millis() as u64, "Loki shipper did not finish in time, pending logs dropped");
+            }
+        }
+        if let Some(file) = &self.file {
+            if !file.flush(timeout) {
+                tracing::warn!(timeout_ms = timeout.as_millis() as u64, "Log file sink did not flush in time");
+            }
+        }
+    }
+
+    fn push_access_log(&self, line: String) {
+        match (&self.loki, &self.file) {
+            (Some(loki), Some(file)) => {
+                file.push(line.clone());
+                loki.push(line);
+            }
+            (Some(loki), None) => loki.push(line),
+            (None, Some(file)) => file.push(line),
+            (None, None) => {}
+        }
+    }
+
+    /// Línea JSON de un evento de la aplicación
+    fn format_event<S>(&self, event: &Event<'_>, ctx: &Context<'_, S>) -> String
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut entry = Map::new();
+
+        // Campos de los spans, del más externo al más interno (los internos prevalecen)
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<SpanFields>() {
+                    entry.extend(fields.0.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+            }
+        }
+
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        let msg = visitor.0.remove("message").unwrap_or(Value::String(String::new()));
+        entry.extend(visitor.0);
+
+        entry.insert("name".to_string(), json!(self.service_name));
+        entry.insert("hostname".to_string(), json!(self.hostname));
+        entry.insert("pid".to_string(), json!(std::process::id()));
+        entry.insert("level".to_string(), json!(bunyan_level(event.metadata().level())));
+        entry.insert("target".to_string(), json!(event.metadata().target()));
+        entry.insert("msg".to_string(), msg);
+        entry.insert("time".to_string(), json!(chrono::Utc::now().to_rfc3339()));
+        entry.insert("v".to_string(), json!(0));
+
+        serde_json::to_string(&entry).unwrap_or_default()
+    }
+}
+
+/// Nivel numérico de bunyan/pino, el mismo que usan los logs de acceso
+fn bunyan_level(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 50,
+        Level::WARN => 40,
+        Level::INFO => 30,
+        Level::DEBUG => 20,
+        Level::TRACE => 10,
+    }
+}
+
+/// Campos registrados de un span (se guardan en sus extensions)
+struct SpanFields(Map<String, Value>);
+
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        // Los campos `otel.*` solo configuran el span exportado por OpenTelemetry
+        if !field.name().starts_with("otel.") {
+            self.0.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, json!(format!("{:?}", value)));
+    }
+}
+
+impl<S> Layer<S> for LogSinks
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, id: &tracing::span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = JsonVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.0));
+    }
+
+    fn on_record(&self, id: &tracing::span::Id, values: &tracing::span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut visitor = JsonVisitor::default();
+        values.record(&mut visitor);
+        let mut extensions = span.extensions_mut();
+        if let Some(fields) = extensions.get_mut::<SpanFields>() {
+            fields.0.extend(visitor.0);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() == ACCESS_LOG_TARGET {
+            let mut visitor = JsonVisitor::default();
+            event.record(&mut visitor);
+            if let Some(Value::String(line)) = visitor.0.remove("line") {
+                self.push_access_log(line);
+            }
+            return;
+        }
+        if event.metadata().target() == loki::LOG_TARGET {
+            return;
+        }
+
+        if let Some(loki) = &self.loki {
+            loki.push(self.format_event(event, &ctx));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn ships_application_and_access_logs_through_the_same_shipper() {
+        let shipper = LokiShipper::unstarted(10);
+        let sinks = LogSinks {
+            loki: Some(shipper.clone()),
+            file: None,
+            service_name: "server-notifications".to_string(),
+            hostname: "test".to_string(),
+        };
+
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(sinks), || {
+            let span = tracing::info_span!("http_request", otel.name = "GET /n", request_id = "req-1");
+            let _guard = span.enter();
+            tracing::warn!(repository = "MongoUserRepository", "Error fetching user");
+            tracing::info!(target: ACCESS_LOG_TARGET, line = r#"{"http":{"statusCode":200}}"#);
+            tracing::error!(target: loki::LOG_TARGET, entries = 10, "Dropping Loki log batch");
+        });
+
+        let lines = shipper.pending_lines();
+        assert_eq!(lines.len(), 2);
+        let app: Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(app["msg"], "Error fetching user");
+        assert_eq!(app["level"], 40);
+        assert_eq!(app["request_id"], "req-1");
+        assert_eq!(app["repository"], "MongoUserRepository");
+        assert_eq!(app["name"], "server-notifications");
+        assert!(app.get("otel.name").is_none());
+        assert_eq!(lines[1], r#"{"http":{"statusCode":200}}"#);
+    }
+}
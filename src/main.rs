@@ -1,15 +1,20 @@
 use actix_web::{App, HttpServer};
-use actix_web::middleware::{NormalizePath, TrailingSlash};
+use actix_web::middleware::{from_fn, NormalizePath, TrailingSlash};
 use std::time::Duration;
 mod routes;
 mod middleware;
 mod types;
 mod domain;
 mod application;
-mod infrastructure { pub mod notification; pub mod session; pub mod user; pub mod analytics; pub mod business; pub mod external; pub mod db; pub mod services; pub mod s3; pub mod providers; }
+mod infrastructure { pub mod notification; pub mod session; pub mod user; pub mod analytics; pub mod business; pub mod feature_flag; pub mod external; pub mod db; pub mod services; pub mod storage; pub mod s3; pub mod cloudfront; pub mod ttl_cache; pub mod signed_url_cache; pub mod existence_cache; pub mod health; pub mod indexes; pub mod log_file; pub mod image_variants; pub mod key_rules; pub mod mongo_monitor; pub mod providers; }
 mod response;
 mod mappers;
 mod controllers;
+mod logging;
+mod metrics;
+mod config;
+mod telemetry;
+mod shutdown;
 
 /// Carga las variables de entorno desde el archivo .env
 fn load_environment() {
@@ -18,69 +23,136 @@ fn load_environment() {
     }
 }
 
-/// Inicializa el sistema de logging con Loki
-fn init_logging(loki_url: &str, hostname: &str) -> std::io::Result<()> {
+/// Filtro de niveles de `logging.level` (`RUST_LOG`), validado al cargar la configuración
+fn env_filter(level: &str) -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::new(level)
+}
+
+/// Inicializa el subsistema de logging: stdout (JSON), Loki/fichero (`LogSinks`) y, si hay endpoint OTLP,
+/// la exportación de trazas. Devuelve el proveedor de trazas para cerrarlo (y enviar los spans pendientes) al terminar
+fn init_logging(
+    log_sinks: logging::LogSinks,
+    level: &str,
+    telemetry_config: &config::TelemetryConfig,
+) -> std::io::Result<Option<opentelemetry_sdk::trace::SdkTracerProvider>> {
+    use tracing_subscriber::filter::{filter_fn, Directive};
     use tracing_subscriber::layer::SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
-    use url::Url;
-    
-    let url = Url::parse(loki_url)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid Loki URL: {}", e)))?;
-    
-    let (layer, task) = tracing_loki::builder()
-        .label("job", "server-notifications")
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Loki label error: {}", e)))?
-        .label("service", "server-notifications")
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Loki label error: {}", e)))?
-        .label("host", hostname)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Loki label error: {}", e)))?
-        .build_url(url)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Loki init error: {}", e)))?;
-    
+    use tracing_subscriber::Layer;
+
+    // Los logs de acceso ya son JSON: van a Loki/fichero siempre (salvo que se filtren), nunca a stdout
+    let access_log: Directive = format!("{}=info", logging::ACCESS_LOG_TARGET)
+        .parse()
+        .map_err(|e| std::io::Error::other(format!("Invalid log directive: {}", e)))?;
+
     let stdout_layer = tracing_subscriber::fmt::layer()
         .json()
-        .with_writer(std::io::stdout);
-    
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
-    
-    eprintln!("[main] Tracing filter: {:?}", filter);
-    
+        .with_writer(std::io::stdout)
+        .with_filter(env_filter(level))
+        .with_filter(filter_fn(|metadata| metadata.target() != logging::ACCESS_LOG_TARGET));
+
+    let sinks_layer = log_sinks.with_filter(env_filter(level).add_directive(access_log));
+
+    let tracer_provider = telemetry::init_tracer_provider(telemetry_config)
+        .map_err(std::io::Error::other)?;
+    let otel_layer = tracer_provider
+        .as_ref()
+        .map(|provider| telemetry::layer(provider, &telemetry_config.service_name).with_filter(env_filter(level)));
+
+    eprintln!("[main] Tracing filter: {:?}", env_filter(level));
+
     tracing_subscriber::registry()
-        .with(filter)
-        .with(layer)
+        .with(otel_layer)
+        .with(sinks_layer)
         .with(stdout_layer)
         .init();
-    
+
     eprintln!("[main] Tracing subscriber inicializado");
+    match &telemetry_config.endpoint {
+        Some(endpoint) => eprintln!("[main] Trazas OTLP: exportando a {} (sampling {})", endpoint, telemetry_config.sample_ratio),
+        None => eprintln!("[main] Trazas OTLP desactivadas (OTEL_EXPORTER_OTLP_ENDPOINT no definido)"),
+    }
     
-    tokio::spawn(async move {
-        eprintln!("[main] Tarea de Loki iniciada");
-        task.await;
-        eprintln!("[main] ⚠️  Tarea de Loki terminó (no debería pasar)");
-    });
-    
-    eprintln!("[main] Logging inicializado: enviando logs a Loki en {}", loki_url);
-    eprintln!("[main] Hostname: {}", hostname);
-    
-    Ok(())
+    Ok(tracer_provider)
 }
 
 /// Crea la configuración de logging compartida
-fn create_logging_config() -> middleware::logging::LoggingConfig {
-    middleware::logging::LoggingConfig::from_env()
+fn create_logging_config(app_config: &config::ConfigHandle) -> middleware::logging::LoggingConfig {
+    middleware::logging::LoggingConfig::new(app_config.clone())
 }
 
-/// Calcula el número de workers basado en CPUs disponibles
-fn calculate_workers() -> usize {
-    std::env::var("ACTIX_WORKERS")
-        .ok()
-        .and_then(|w| w.parse::<usize>().ok())
-        .unwrap_or_else(|| {
-            std::thread::available_parallelism()
-                .map(|n| n.get())
-                .unwrap_or(1)
-        })
+/// Crea los destinos de los logs
+/// - `logging.loki.enabled` (default: true): push por lotes a Loki de todos los logs (aplicación y acceso)
+/// - `logging.file.enabled` (default: false): fichero con rotación para promtail con los logs de acceso
+fn create_log_sinks(
+    config: &middleware::logging::LoggingConfig,
+    settings: &config::LoggingConfig,
+) -> std::io::Result<logging::LogSinks> {
+    use infrastructure::external::loki::LokiShipper;
+    use infrastructure::log_file::FileLogSink;
+
+    let loki = settings.loki.enabled.then(|| {
+        eprintln!("[main] Sending logs to Loki at {} (host {})", config.loki_url, config.hostname);
+        LokiShipper::start(settings.loki.clone(), config.loki_labels())
+    });
+
+    let file = if settings.file.enabled {
+        let file_config = &settings.file;
+        eprintln!("[main] Writing access logs to {}/{}.log", file_config.dir.display(), file_config.name);
+        Some(FileLogSink::start(file_config.clone())
+            .map_err(|e| std::io::Error::other(format!("log file init error: {}", e)))?)
+    } else {
+        None
+    };
+
+    Ok(logging::LogSinks::new(config, loki, file))
+}
+
+/// Argumentos de línea de comandos
+/// - `--config <ruta>`: fichero TOML de configuración (alternativa a `APP_CONFIG_FILE`)
+/// - `--check-config`: valida la configuración, imprime la efectiva (secretos redactados) y termina
+/// - `--ensure-indexes`: crea al arrancar los índices de MongoDB que faltan (ver `check_indexes`)
+struct CliArgs {
+    config_path: Option<std::path::PathBuf>,
+    check_config: bool,
+    ensure_indexes: bool,
+}
+
+fn parse_args() -> std::io::Result<CliArgs> {
+    let mut cli = CliArgs { config_path: None, check_config: false, ensure_indexes: false };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check-config" => cli.check_config = true,
+            "--ensure-indexes" => cli.ensure_indexes = true,
+            "--config" => {
+                let path = args.next().ok_or_else(|| std::io::Error::other("--config requires a path"))?;
+                cli.config_path = Some(path.into());
+            }
+            other => return Err(std::io::Error::other(format!("Unknown argument: {}", other))),
+        }
+    }
+    Ok(cli)
+}
+
+/// Carga y valida la configuración; si no es válida lista todos los problemas y no arranca
+fn load_config(path: Option<&std::path::Path>) -> std::io::Result<config::AppConfig> {
+    config::AppConfig::load(path).map_err(|problems| {
+        eprintln!("[main] Invalid configuration:");
+        for problem in &problems {
+            eprintln!("[main]   - {}", problem);
+        }
+        std::io::Error::other(format!("invalid configuration ({} problems)", problems.len()))
+    })
+}
+
+/// Calcula el número de workers basado en CPUs disponibles (o `server.workers` si está configurado)
+fn calculate_workers(server: &config::ServerConfig) -> usize {
+    server.workers.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
 }
 
 /// Calcula la configuración del pool de MongoDB basado en el número de workers
@@ -100,29 +172,69 @@ fn calculate_mongodb_pool_config(num_workers: usize) -> (u32, u32, usize) {
     (min_pool_size, max_pool_size, total_connections)
 }
 
-/// Obtiene el puerto del servidor desde variable de entorno o usa el default
-fn get_server_port() -> u16 {
-    std::env::var("API_PORT")
-        .unwrap_or_else(|_| "8080".to_string())
-        .parse::<u16>()
-        .unwrap_or(8080)
-}
-
 /// Inicializa las bases de datos de MongoDB
-async fn init_databases(max_pool_size: u32, min_pool_size: u32) -> std::io::Result<infrastructure::db::Databases> {
+async fn init_databases(
+    config: &config::MongoConfig,
+    max_pool_size: u32,
+    min_pool_size: u32,
+) -> std::io::Result<infrastructure::db::Databases> {
     infrastructure::db::Databases::init_with_pool_config(
+        config,
         Some(max_pool_size),
         Some(min_pool_size),
     ).await
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("mongo init error: {}", e)))
+        .map_err(|e| std::io::Error::other(format!("mongo init error: {}", e)))
+}
+
+/// Verifica los índices que declaran los repositorios según `mongodb.index_check` o, con `--ensure-indexes`,
+/// crea los que faltan. Las diferencias que no se pueden corregir (índices distintos) solo se reportan;
+/// con "fail" impiden arrancar, igual que no poder crear o listar los índices con `--ensure-indexes`
+async fn check_indexes(
+    databases: &infrastructure::db::Databases,
+    mode: config::IndexCheckMode,
+    ensure: bool,
+) -> std::io::Result<()> {
+    use config::IndexCheckMode;
+    use infrastructure::indexes::IndexManager;
+
+    if mode == IndexCheckMode::Off && !ensure {
+        return Ok(());
+    }
+
+    let manager = IndexManager::new(databases);
+    let report = if ensure { manager.ensure().await } else { manager.verify().await };
+    let report = match report {
+        Ok(report) => report,
+        Err(e) if ensure || mode == IndexCheckMode::Fail => {
+            tracing::error!(error = %e, "Could not check MongoDB indexes");
+            return Err(std::io::Error::other(format!("index check error: {}", e)));
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not check MongoDB indexes, starting anyway");
+            return Ok(());
+        }
+    };
+
+    report.log();
+    let problems = report.problems().count();
+    if problems > 0 && mode == IndexCheckMode::Fail {
+        return Err(std::io::Error::other(format!(
+            "{} required MongoDB indexes missing or different (run with --ensure-indexes)",
+            problems
+        )));
+    }
+    Ok(())
 }
 
 /// Inicializa todos los servicios de la aplicación
-async fn init_services(databases: &infrastructure::db::Databases) -> std::io::Result<infrastructure::services::AppServices> {
-    infrastructure::services::AppServices::new(databases).await
+async fn init_services(
+    databases: &infrastructure::db::Databases,
+    config: config::ConfigHandle,
+) -> std::io::Result<infrastructure::services::AppServices> {
+    infrastructure::services::AppServices::new(databases, config).await
         .map_err(|e| {
             eprintln!("[main] Error initializing services: {}", e);
-            std::io::Error::new(std::io::ErrorKind::Other, format!("services init error: {}", e))
+            std::io::Error::other(format!("services init error: {}", e))
         })
 }
 
@@ -132,36 +244,68 @@ async fn start_server(
     port: u16,
     num_workers: usize,
     logging_config: middleware::logging::LoggingConfig,
+    shutdown_config: &config::ShutdownConfig,
 ) -> std::io::Result<()> {
-    HttpServer::new(move || App::new()
+    let readiness = services.readiness.clone();
+    let server = HttpServer::new(move || App::new()
         .wrap(middleware::logging::StructuredLogging::new(logging_config.clone()))
+        .wrap(from_fn(middleware::metrics::http_metrics))
+        .wrap(from_fn(middleware::trace_context::trace_context))
+        .wrap(from_fn(middleware::request_id::request_id))
         .wrap(NormalizePath::new(TrailingSlash::Trim))
         .app_data(actix_web::web::Data::new(services.clone()))
         .service(routes::health::router())
-        .service(routes::notification::router()))
+        .service(routes::metrics::router())
+        .service(routes::notification::router())
+        .service(routes::admin::router()))
         .bind(("0.0.0.0", port))?
         .workers(num_workers)
         .client_request_timeout(Duration::from_millis(5000))
         .client_disconnect_timeout(Duration::from_millis(1000))
         .keep_alive(Duration::from_secs(30))
         .backlog(8192)
-        .run()
-        .await
+        .shutdown_timeout(shutdown_config.grace_period_secs)
+        // Las señales se gestionan aquí para marcar el servicio como no listo antes de parar
+        .disable_signals()
+        .run();
+
+    let handle = server.handle();
+    let readiness_delay = shutdown_config.readiness_delay();
+    let grace_period_secs = shutdown_config.grace_period_secs;
+    actix_web::rt::spawn(async move {
+        let signal = shutdown::wait_for_signal().await;
+        tracing::info!(signal, delay_ms = readiness_delay.as_millis() as u64, "Shutdown requested, marking service as not ready");
+        readiness.mark_shutting_down();
+        tokio::time::sleep(readiness_delay).await;
+        tracing::info!(timeout_secs = grace_period_secs, "Stopping server and draining in-flight requests");
+        handle.stop(true).await;
+    });
+
+    server.await
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     load_environment();
+    let cli = parse_args()?;
+    let config_path = config::AppConfig::file_path(cli.config_path.as_deref());
+    let app_config = load_config(config_path.as_deref())?;
+    if cli.check_config {
+        println!("{}", app_config.to_redacted_toml());
+        return Ok(());
+    }
+    let config_handle = config::ConfigHandle::new(app_config, config_path);
+    let app_config = config_handle.current();
     
     // Crear configuración de logging una sola vez (se reutiliza en middleware)
-    let logging_config = create_logging_config();
+    let logging_config = create_logging_config(&config_handle);
     
-    // Inicializar tracing-loki (solo para stdout, el middleware envía directamente a Loki)
-    let loki_url = logging_config.loki_url.clone();
-    let hostname = logging_config.hostname.clone();
-    init_logging(&loki_url, &hostname)?;
+    // Destinos de los logs (una sola tarea/hilo para todos los workers) y subscriber de tracing
+    let log_sinks = create_log_sinks(&logging_config, &app_config.logging)?;
+    let tracer_provider = init_logging(log_sinks.clone(), &app_config.logging.level, &app_config.telemetry)?;
+    let shutdown_config = app_config.shutdown.clone();
     
-    let num_workers = calculate_workers();
+    let num_workers = calculate_workers(&app_config.server);
     let (min_pool_size, max_pool_size, total_connections) = calculate_mongodb_pool_config(num_workers);
     let connections_per_worker = max_pool_size as usize;
     
@@ -171,9 +315,23 @@ async fn main() -> std::io::Result<()> {
     println!("[main] MongoDB pool: {} connections per worker (total: {} connections)", 
         connections_per_worker, total_connections);
 
-    let databases = init_databases(max_pool_size, min_pool_size).await?;
-    let services = init_services(&databases).await?;
-    let port = get_server_port();
+    let databases = init_databases(&app_config.mongodb, max_pool_size, min_pool_size).await?;
+    check_indexes(&databases, app_config.mongodb.index_check, cli.ensure_indexes).await?;
+    let port = app_config.server.port;
+    let services = init_services(&databases, config_handle.clone()).await?;
+
+    // Recarga en caliente con SIGHUP (o al cambiar el fichero, ver `ConfigHandle::watch`)
+    actix_web::rt::spawn(config_handle.watch());
     
-    start_server(services, port, num_workers, logging_config).await
+    let result = start_server(services, port, num_workers, logging_config, &shutdown_config).await;
+
+    // Requests drenados: enviar lo que quede en los workers en segundo plano antes de salir
+    tracing::info!(timeout_secs = shutdown_config.flush_timeout_secs, "Server stopped, flushing logs and traces");
+    log_sinks.flush(shutdown_config.flush_timeout()).await;
+    if let Some(provider) = tracer_provider {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("[main] Error flushing OTLP traces: {}", e);
+        }
+    }
+    result
 }
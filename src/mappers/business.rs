@@ -1,12 +1,100 @@
-use mongodb::bson::Document;
-use crate::domain::{Business, BusinessRepoError};
+use mongodb::bson::{Bson, Document};
+use std::collections::HashMap;
+
+use crate::config::{is_bucket_name, is_hex_color, is_http_url, is_language_tag};
+use crate::domain::{Business, BusinessRepoError, TenantSettings};
 
 // Infra -> Dominio
 pub fn doc_to_domain(doc: Document) -> Result<Business, BusinessRepoError> {
     let name = doc.get_str("name")
         .map_err(|e| BusinessRepoError::Unexpected(format!("Error reading name field: {}", e)))?
         .to_string();
-    
-    Ok(Business { name })
+
+    // `notificationsConfig` es opcional: los businesses sin él usan los valores globales
+    let business_id = doc.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default();
+    let settings = doc.get_document("notificationsConfig")
+        .map(|config| settings_from_doc(&business_id, config))
+        .unwrap_or_default();
+
+    Ok(Business { name, settings })
 }
 
+/// Lee la configuración de notificaciones del business (campos en camelCase, todos opcionales)
+/// Ejemplo: `{ brandName, defaultLanguage, logoUrl, primaryColor, secondaryColor, bucket, cdnBaseUrl,
+/// allowedPlatforms: ["iOS"], features: { tracking: false } }`
+/// Los valores que no pasarían la validación de `[tenants.overrides]` se ignoran con un warning
+fn settings_from_doc(business_id: &str, doc: &Document) -> TenantSettings {
+    let text = |field: &str| {
+        doc.get_str(field)
+            .ok()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+    };
+    let checked = |field: &str, is_valid: fn(&str) -> bool| {
+        text(field).filter(|value| {
+            let valid = is_valid(value);
+            if !valid {
+                tracing::warn!(business_id, field, value = %value, "Ignoring invalid notificationsConfig value");
+            }
+            valid
+        })
+    };
+
+    let allowed_platforms = doc.get_array("allowedPlatforms").ok().map(|platforms| {
+        platforms.iter().filter_map(Bson::as_str).map(|p| p.trim().to_string()).collect()
+    });
+    let features: HashMap<String, bool> = doc.get_document("features")
+        .map(|features| {
+            features.iter()
+                .filter_map(|(name, value)| value.as_bool().map(|enabled| (name.clone(), enabled)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TenantSettings {
+        brand_name: text("brandName"),
+        default_language: checked("defaultLanguage", is_language_tag),
+        logo_url: checked("logoUrl", is_http_url),
+        primary_color: checked("primaryColor", is_hex_color),
+        secondary_color: checked("secondaryColor", is_hex_color),
+        bucket: checked("bucket", is_bucket_name),
+        cdn_base_url: checked("cdnBaseUrl", is_http_url),
+        allowed_platforms,
+        features,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn reads_optional_notifications_config() {
+        let business = doc_to_domain(doc! {
+            "name": "Club Padel",
+            "notificationsConfig": {
+                "brandName": "Padel+",
+                "logoUrl": "",
+                "primaryColor": "#0A84FF",
+                "secondaryColor": "blue",
+                "cdnBaseUrl": "cdn.example.com",
+                "allowedPlatforms": ["iOS", "Android"],
+                "features": { "tracking": false, "badge": "yes" },
+            },
+        })
+        .unwrap();
+
+        assert_eq!(business.settings.brand_name.as_deref(), Some("Padel+"));
+        assert_eq!(business.settings.logo_url, None);
+        assert_eq!(business.settings.primary_color.as_deref(), Some("#0A84FF"));
+        assert_eq!(business.settings.secondary_color, None);
+        assert_eq!(business.settings.cdn_base_url, None);
+        assert_eq!(business.settings.allowed_platforms, Some(vec!["iOS".to_string(), "Android".to_string()]));
+        assert_eq!(business.settings.features, HashMap::from([("tracking".to_string(), false)]));
+
+        let plain = doc_to_domain(doc! { "name": "Goil" }).unwrap();
+        assert_eq!(plain.settings, TenantSettings::default());
+    }
+}
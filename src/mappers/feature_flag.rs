@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::{Bson, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{FeatureFlag, FeatureFlagRepoError, FeatureFlagUpdate};
+
+// Infra -> Dominio
+pub fn doc_to_domain(doc: Document) -> Result<FeatureFlag, FeatureFlagRepoError> {
+    let key = doc.get_str("key")
+        .map_err(|e| FeatureFlagRepoError::Unexpected(format!("Error reading key field: {}", e)))?
+        .to_string();
+    let strings = |field: &str| -> Vec<String> {
+        doc.get_array(field)
+            .map(|values| values.iter().filter_map(Bson::as_str).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    // Se acepta int32, int64 o double (según quién haya escrito el documento)
+    let percentage = match doc.get("percentage") {
+        Some(Bson::Int32(p)) => *p as i64,
+        Some(Bson::Int64(p)) => *p,
+        Some(Bson::Double(p)) => *p as i64,
+        _ => 100,
+    };
+
+    Ok(FeatureFlag {
+        key,
+        description: doc.get_str("description").unwrap_or_default().to_string(),
+        enabled: doc.get_bool("enabled").unwrap_or(false),
+        business_ids: strings("businessIds"),
+        account_type_ids: strings("accountTypeIds"),
+        percentage: percentage.clamp(0, 100) as u8,
+        updated_at: doc.get_datetime("updatedAt")
+            .ok()
+            .and_then(|dt| DateTime::<Utc>::from_timestamp_millis(dt.timestamp_millis())),
+    })
+}
+
+// Dominio -> Response DTO
+#[allow(non_snake_case)] // Los nombres están en camelCase para la API externa
+#[derive(Serialize)]
+pub struct FeatureFlagDto {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    pub businessIds: Vec<String>,
+    pub accountTypeIds: Vec<String>,
+    pub percentage: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updatedAt: Option<String>,
+}
+
+pub fn domain_to_dto(flag: FeatureFlag) -> FeatureFlagDto {
+    FeatureFlagDto {
+        key: flag.key,
+        description: flag.description,
+        enabled: flag.enabled,
+        businessIds: flag.business_ids,
+        accountTypeIds: flag.account_type_ids,
+        percentage: flag.percentage,
+        updatedAt: flag.updated_at.map(|dt| dt.to_rfc3339()),
+    }
+}
+
+// Request DTO -> Dominio
+/// Cuerpo de `PATCH /api/v2/admin/flags/{key}`
+/// Ejemplo: `{ "enabled": true, "percentage": 10 }`
+#[derive(Deserialize)]
+pub struct FeatureFlagUpdateRequest {
+    pub enabled: bool,
+    pub percentage: Option<u8>,
+}
+
+pub fn update_request_to_domain(req: &FeatureFlagUpdateRequest) -> Result<FeatureFlagUpdate, String> {
+    if req.percentage.is_some_and(|p| p > 100) {
+        return Err("percentage must be between 0 and 100".to_string());
+    }
+    Ok(FeatureFlagUpdate { enabled: req.enabled, percentage: req.percentage })
+}
@@ -3,3 +3,4 @@ pub mod notification;
 pub mod session;
 pub mod user;
 pub mod business;
+pub mod feature_flag;
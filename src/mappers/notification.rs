@@ -1,11 +1,12 @@
 use mongodb::bson::Document;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::domain::{Notification, NotificationRepoError};
+use crate::domain::{Notification, NotificationEvent, NotificationRepoError, TenantConfig};
 use crate::mappers::common::object_id_to_string_or_empty;
+use crate::types::ImageHint;
 
 // Infra -> Dominio
-// language: idioma a usar para i18n, por defecto "es"
+// language: idioma a usar para i18n (el de la sesión o, si no tiene, el del tenant)
 pub fn doc_to_domain(doc: Document, language: &str) -> Result<Notification, NotificationRepoError> {
     let id = object_id_to_string_or_empty(doc.get_object_id("_id").ok());
     
@@ -77,6 +78,20 @@ pub struct NotificationResponse {
     pub businessName: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub businessId: Option<String>,
+    /// Logo y colores del business (omitido si no tiene)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branding: Option<BrandingDto>,
+}
+
+#[allow(non_snake_case)] // Los nombres están en camelCase para la API externa
+#[derive(Serialize)]
+pub struct BrandingDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logoUrl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primaryColor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondaryColor: Option<String>,
 }
 
 #[allow(non_snake_case)] // Los nombres están en camelCase para la API externa
@@ -91,31 +106,37 @@ pub struct NotificationDto {
     pub r#type: i32, // r#type porque "type" es una palabra reservada en Rust
     pub payloadType: i32,
     pub isRead: bool,
+    /// Índices de `imageUrls` cuya imagen no existe (solo con IMAGE_EXISTENCE_CHECK=mark)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missingImageIndexes: Vec<usize>,
 }
 
+/// `unread_count`: None si el tenant desactiva el badge (toggle "badge")
 pub async fn domain_to_response(
     n: Notification, 
-    s3_signer: &crate::infrastructure::s3::S3UrlSigner,
-    business_id: Option<String>,
-    business_name: Option<String>,
-    unread_count: i32,
+    storage: &crate::infrastructure::providers::StorageServiceProvider,
+    tenant: &TenantConfig,
+    unread_count: Option<i32>,
+    image_hint: &ImageHint,
+    expires_in: u64,
 ) -> NotificationResponse {
-    // Firmar URLs de S3 para imageUrls
-    // Duración por defecto: 600 segundos (10 minutos)
-    let expires_in = std::env::var("S3_URL_EXPIRES_IN")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .unwrap_or(600);
-    
-    let image_urls = if n.image_paths.is_empty() {
-        Vec::new()
+    // Construir URLs de imageUrls con la estrategia del business (S3 presign, CloudFront o CDN público)
+    // `expires_in`: validez de las URLs firmadas en segundos (`AppConfig::storage`, default: 600)
+    let business_id = tenant.business_id.as_str();
+    let (image_urls, missing_image_indexes) = if n.image_paths.is_empty() {
+        (Vec::new(), Vec::new())
     } else {
-        s3_signer.sign_urls(&n.image_paths, expires_in).await
-            .unwrap_or_else(|e| {
-                eprintln!("[domain_to_response] Error signing S3 URLs: {}", e);
+        match storage.image_urls(Some(tenant), &n.image_paths, image_hint, expires_in).await {
+            Ok(images) => {
+                let missing = images.iter().enumerate().filter(|(_, img)| img.missing).map(|(i, _)| i).collect();
+                (images.into_iter().map(|img| img.url).collect(), missing)
+            }
+            Err(e) => {
+                tracing::error!(business_id, error = %e, "Error building image URLs");
                 // Si falla la firma, retornar las rutas originales
-                n.image_paths.clone()
-            })
+                (n.image_paths.clone(), Vec::new())
+            }
+        }
     };
     
     let dto = NotificationDto {
@@ -128,12 +149,91 @@ pub async fn domain_to_response(
         r#type: n.r#type,
         payloadType: n.payload_type,
         isRead: n.is_read,
+        missingImageIndexes: missing_image_indexes,
     };
     
+    let has_branding = tenant.logo_url.is_some() || tenant.primary_color.is_some() || tenant.secondary_color.is_some();
+    let branding = has_branding.then(|| BrandingDto {
+        logoUrl: tenant.logo_url.clone(),
+        primaryColor: tenant.primary_color.clone(),
+        secondaryColor: tenant.secondary_color.clone(),
+    });
+
     NotificationResponse { 
         notification: dto,
-        badge: Some(unread_count),
-        businessName: business_name,
-        businessId: business_id,
+        badge: unread_count,
+        businessName: Some(tenant.brand_name.clone()),
+        businessId: Some(tenant.business_id.clone()),
+        branding,
+    }
+}
+
+// Request DTO -> Dominio
+/// Cuerpo de `POST /api/v2/notification/{id}/events`
+/// Ejemplo: `{ "type": "clicked", "targetUrl": "https://...", "occurredAt": 1700000000000 }`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationEventRequest {
+    #[serde(flatten)]
+    pub event: NotificationEventKind,
+    /// Momento en que ocurrió el evento en el dispositivo (epoch millis)
+    pub occurred_at: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationEventKind {
+    Delivered,
+    Opened,
+    Clicked {
+        #[serde(rename = "targetUrl")]
+        target_url: String,
+    },
+    Dismissed,
+}
+
+pub fn event_request_to_domain(req: &NotificationEventRequest) -> Result<NotificationEvent, String> {
+    let event = match &req.event {
+        NotificationEventKind::Delivered => NotificationEvent::Delivered,
+        NotificationEventKind::Opened => NotificationEvent::Opened,
+        NotificationEventKind::Clicked { target_url } => {
+            let target_url = target_url.trim();
+            // Acepta cualquier esquema (https, deep links de la app...) siempre que sea una URL válida
+            url::Url::parse(target_url).map_err(|_| "Invalid targetUrl".to_string())?;
+            NotificationEvent::Clicked { target_url: target_url.to_string() }
+        }
+        NotificationEventKind::Dismissed => NotificationEvent::Dismissed,
+    };
+    Ok(event)
+}
+
+#[allow(non_snake_case)] // Los nombres están en camelCase para la API externa
+#[derive(Serialize)]
+pub struct NotificationEventAckDto {
+    pub notificationId: String,
+    pub event: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> Result<NotificationEventRequest, serde_json::Error> {
+        serde_json::from_str(body)
+    }
+
+    #[test]
+    fn validates_engagement_events() {
+        let clicked = parse(r#"{"type":"clicked","targetUrl":" goil://club/1 ","occurredAt":1700000000000}"#).unwrap();
+        assert_eq!(clicked.occurred_at, Some(1700000000000));
+        assert_eq!(
+            event_request_to_domain(&clicked).unwrap(),
+            NotificationEvent::Clicked { target_url: "goil://club/1".to_string() }
+        );
+
+        assert!(parse(r#"{"type":"shared"}"#).is_err());
+        assert!(parse(r#"{"type":"clicked"}"#).is_err());
+        let invalid_url = parse(r#"{"type":"clicked","targetUrl":"not a url"}"#).unwrap();
+        assert_eq!(event_request_to_domain(&invalid_url).unwrap_err(), "Invalid targetUrl");
     }
 }
@@ -4,6 +4,10 @@ use crate::domain::{Session, SessionRepoError};
 
 // Infra -> Dominio
 pub fn doc_to_domain(doc: Document) -> Result<Session, SessionRepoError> {
-    let language = doc.get_str("language").unwrap_or("es").to_string();
+    let language = doc.get_str("language")
+        .ok()
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .map(String::from);
     Ok(Session { language })
 }
\ No newline at end of file
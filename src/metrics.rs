@@ -0,0 +1,192 @@
+use prometheus::{Encoder, Histogram, HistogramOpts, HistogramVec, IntCounterVec, Opts, TextEncoder};
+use std::future::IntoFuture;
+use std::sync::LazyLock;
+use std::time::Instant;
+use tracing::Instrument;
+
+/// Registra un collector en el registro por defecto de Prometheus, el mismo que usan las métricas
+/// definidas junto a su módulo; los nombres son constantes, así que un fallo es un bug
+fn register<C: prometheus::core::Collector + Clone + 'static>(collector: C) -> C {
+    prometheus::default_registry()
+        .register(Box::new(collector.clone()))
+        .expect("metric registered twice");
+    collector
+}
+
+/// Latencia de las peticiones HTTP por ruta (patrón, no path real) y status
+pub static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route pattern and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Latencia de las queries de MongoDB por repositorio y operación
+pub static MONGO_QUERY_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("mongo_query_duration_seconds", "MongoDB query latency by repository and operation")
+                .buckets(vec![0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]),
+            &["repository", "operation"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Errores de MongoDB por repositorio y operación
+pub static MONGO_QUERY_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("mongo_query_errors_total", "MongoDB query errors by repository and operation"),
+            &["repository", "operation"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Llamadas a servicios externos (GetStream, cola) por resultado: "success" o "error"
+pub static EXTERNAL_CALLS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("external_calls_total", "Calls to external services by outcome"),
+            &["service", "operation", "outcome"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Tiempo de firma de URLs de S3 (solo fallos de caché: las URLs cacheadas no se firman)
+pub static S3_SIGN_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("s3_sign_duration_seconds", "Time spent presigning S3 URLs")
+                .buckets(vec![0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05]),
+            &["bucket"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Cálculos del unread count con datos incompletos, por la fuente que falló
+pub static UNREAD_COUNT_DEGRADED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("unread_count_degraded_total", "Unread counts computed without one of their sources"),
+            &["source"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Latencia de los comandos de MongoDB por colección y comando (del command monitoring del driver)
+pub static MONGO_COMMAND_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new("mongo_command_duration_seconds", "MongoDB command latency by collection and command"),
+            &["collection", "command", "outcome"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Comandos de MongoDB que superaron `MONGO_SLOW_QUERY_MS`
+pub static MONGO_SLOW_COMMANDS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("mongo_slow_commands_total", "MongoDB commands slower than the configured threshold"),
+            &["collection", "command"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Tiempo de espera para obtener una conexión del pool de MongoDB
+pub static MONGO_POOL_CHECKOUT_WAIT: LazyLock<Histogram> = LazyLock::new(|| {
+    register(
+        Histogram::with_opts(
+            HistogramOpts::new("mongo_pool_checkout_wait_seconds", "Time spent waiting for a MongoDB pool connection")
+                .buckets(vec![0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0]),
+        )
+        .unwrap(),
+    )
+});
+
+/// Checkouts del pool de MongoDB fallidos (timeout de la cola de espera o error de conexión)
+pub static MONGO_POOL_CHECKOUT_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("mongo_pool_checkout_failures_total", "Failed MongoDB pool checkouts"),
+            &["reason"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Recargas de la configuración (`success` o `invalid`: se mantiene la anterior)
+pub static CONFIG_RELOADS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new("config_reloads_total", "Configuration reload attempts"),
+            &["outcome"],
+        )
+        .unwrap(),
+    )
+});
+
+/// Mide una query de MongoDB y cuenta sus errores (también abre su span de traza y registra el error)
+pub async fn observe_mongo<T, E: std::fmt::Display>(
+    repository: &str,
+    operation: &str,
+    query: impl IntoFuture<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let span = tracing::info_span!(
+        "mongodb",
+        otel.name = %format!("{}.{}", repository, operation),
+        otel.kind = "client",
+        db.system = "mongodb",
+    );
+    let start = Instant::now();
+    let result = query.into_future().instrument(span).await;
+    MONGO_QUERY_DURATION
+        .with_label_values(&[repository, operation])
+        .observe(start.elapsed().as_secs_f64());
+    if let Err(e) = &result {
+        MONGO_QUERY_ERRORS.with_label_values(&[repository, operation]).inc();
+        tracing::error!(repository, operation, error = %e, "MongoDB query failed");
+    }
+    result
+}
+
+/// Cuenta el resultado de una llamada a un servicio externo (error de red o status no 2xx = "error")
+pub fn record_external_call(service: &str, operation: &str, success: bool) {
+    let outcome = if success { "success" } else { "error" };
+    EXTERNAL_CALLS.with_label_values(&[service, operation, outcome]).inc();
+}
+
+/// Exposición en formato texto de Prometheus
+pub fn render() -> Result<String, String> {
+    // Forzar el registro de todas las métricas (las que no tienen labels aparecen a 0 desde el arranque)
+    LazyLock::force(&HTTP_REQUEST_DURATION);
+    LazyLock::force(&MONGO_QUERY_DURATION);
+    LazyLock::force(&MONGO_QUERY_ERRORS);
+    LazyLock::force(&MONGO_COMMAND_DURATION);
+    LazyLock::force(&MONGO_SLOW_COMMANDS);
+    LazyLock::force(&MONGO_POOL_CHECKOUT_WAIT);
+    LazyLock::force(&MONGO_POOL_CHECKOUT_FAILURES);
+    LazyLock::force(&EXTERNAL_CALLS);
+    LazyLock::force(&S3_SIGN_DURATION);
+    LazyLock::force(&UNREAD_COUNT_DEGRADED);
+    LazyLock::force(&crate::infrastructure::providers::storage::MISSING_IMAGES);
+    LazyLock::force(&crate::infrastructure::external::loki::LOKI_DROPPED_ENTRIES);
+    LazyLock::force(&crate::infrastructure::log_file::LOG_FILE_DROPPED_ENTRIES);
+    LazyLock::force(&CONFIG_RELOADS);
+
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&prometheus::gather(), &mut buffer)
+        .map_err(|e| format!("Error encoding metrics: {}", e))?;
+    String::from_utf8(buffer).map_err(|e| format!("Error encoding metrics: {}", e))
+}
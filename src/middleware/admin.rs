@@ -0,0 +1,58 @@
+use actix_web::{dev::ServiceRequest, dev::ServiceResponse, Error, HttpResponse};
+use actix_web::body::BoxBody; // BoxBody: tipo único para el cuerpo de la respuesta que evita genéricos opacos en middlewares
+use actix_web::middleware::Next;
+use crate::infrastructure::services::AppServices;
+use crate::response::ApiResponse;
+
+static ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+fn admin_error(status: actix_web::http::StatusCode, msg: &str) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type("application/json")
+        .body(serde_json::to_string(&ApiResponse::<()>::error(msg)).unwrap())
+}
+
+/// Compara en tiempo constante para no filtrar el token por tiempos de respuesta
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Middleware: protege los endpoints internos de administración
+/// El header `x-admin-token` debe coincidir con `admin.api_token` de la configuración en uso (recargable);
+/// con `admin.enabled = false` la API admin responde 404
+pub async fn admin_guard(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let admin = match req.app_data::<actix_web::web::Data<AppServices>>() {
+        Some(services) => services.config.current().admin.clone(),
+        None => Default::default(),
+    };
+    // La validación exige el token si está habilitada; se comprueba igualmente para no aceptar un token vacío
+    let expected = admin.api_token.expose();
+    if !admin.enabled || admin.api_token.is_empty() {
+        return Ok(req.into_response(
+            admin_error(actix_web::http::StatusCode::NOT_FOUND, "Admin API disabled").map_into_boxed_body(),
+        ));
+    }
+
+    let authorized = req
+        .headers()
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|token| token_matches(token.trim(), expected));
+
+    if !authorized {
+        return Ok(req.into_response(
+            admin_error(actix_web::http::StatusCode::UNAUTHORIZED, "Invalid admin token").map_into_boxed_body(),
+        ));
+    }
+
+    let res = next.call(req).await?.map_into_boxed_body();
+    Ok(res)
+}
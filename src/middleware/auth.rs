@@ -4,6 +4,7 @@ use actix_web::http::header::AUTHORIZATION;
 use actix_web::middleware::Next;
 use actix_web::HttpMessage; // para extensions_mut()
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use crate::infrastructure::services::AppServices;
 use crate::response::ApiResponse;
 
 use crate::types::{AuthContext, JwtClaims};
@@ -34,15 +35,13 @@ pub async fn auth_guard( // middleware: valida y decodifica JWT HS256 y añade A
 
     // x-client-platform: reservado para validación futura si es necesario
 
-    // Config JWT: HS256 fijo; secreto desde entorno (o valor por defecto en dev)
-    // Intenta JWT_SECRET primero, luego JWT_MOBILE_PLATFORM (para compatibilidad)
-    let secret = match std::env::var("JWT_MOBILE_PLATFORM") {
-        Ok(val) => val,
-        Err(_) => {
-            let resp = internal_error("JWT_MOBILE_PLATFORM environment variable not set");
-            return Ok(req.into_response(resp.map_into_boxed_body()));
-        }
+    // Config JWT: HS256 fijo; secreto de la configuración en uso (JWT_MOBILE_PLATFORM, recargable)
+    let Some(services) = req.app_data::<actix_web::web::Data<AppServices>>() else {
+        return Ok(req.into_response(
+            internal_error("Services not available").map_into_boxed_body(),
+        ));
     };
+    let secret = services.config.current().auth.jwt_secret.expose().to_string();
 
     // Decodificar y validar firma con HS256
     // Si el token está expirado pero la firma es válida, extraemos los claims igualmente
@@ -95,7 +94,7 @@ pub async fn auth_guard( // middleware: valida y decodifica JWT HS256 y añade A
         user_id: claims.user_id,
         account_type_id: claims.type_id,
         session_id: claims.session_id.clone(),
-        business_id: business_id,
+        business_id,
     };
     
     // Insertar AuthContext en extensions para que los middlewares/handlers siguientes lo usen
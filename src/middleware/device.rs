@@ -0,0 +1,380 @@
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use actix_web::http::header::{HeaderMap, USER_AGENT};
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+
+use crate::types::{DeviceInfo, DeviceType};
+
+static CLIENT_DEVICE_HEADER: &str = "x-client-device";
+static CLIENT_DEVICE_TYPE_HEADER: &str = "x-client-device-type";
+static CLIENT_OS_HEADER: &str = "x-client-os";
+// x-app-version es el header actual; x-client-version lo envían versiones antiguas de la app
+static APP_VERSION_HEADERS: [&str; 2] = ["x-app-version", "x-client-version"];
+
+/// Productos del User-Agent que corresponden a librerías, motores o al SO (nunca a la app)
+const NON_APP_PRODUCTS: &[&str] = &[
+    "mozilla", "applewebkit", "chrome", "safari", "version", "mobile", "gecko", "firefox",
+    "dalvik", "okhttp", "dart", "cfnetwork", "darwin", "alamofire", "expo", "reactnative",
+];
+
+impl DeviceInfo {
+    /// Construye la información del dispositivo a partir de los headers del request
+    /// Los headers explícitos (`x-client-*`, `x-app-version`) tienen prioridad sobre el User-Agent
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        let from_ua = header(USER_AGENT.as_str())
+            .map(parse_user_agent)
+            .unwrap_or_default();
+
+        // SO: x-client-os manda; si no trae versión y coincide con el del User-Agent, usamos la de este
+        let (os_name, os_version) = match header(CLIENT_OS_HEADER).map(parse_os) {
+            Some((name, version)) => {
+                let version = version.or_else(|| {
+                    (from_ua.os_name.as_deref() == Some(name.as_str()))
+                        .then(|| from_ua.os_version.clone())
+                        .flatten()
+                });
+                (Some(name), version)
+            }
+            None => (from_ua.os_name.clone(), from_ua.os_version.clone()),
+        };
+
+        let model = header(CLIENT_DEVICE_HEADER)
+            .map(String::from)
+            .or(from_ua.model);
+
+        let device_type = header(CLIENT_DEVICE_TYPE_HEADER)
+            .and_then(DeviceType::parse)
+            .or_else(|| model.as_deref().and_then(device_type_from_model))
+            .or(from_ua.device_type);
+
+        let app_version = APP_VERSION_HEADERS
+            .iter()
+            .find_map(|name| header(name))
+            .map(String::from)
+            .or(from_ua.app_version);
+
+        Self {
+            device_type,
+            model,
+            os_name,
+            os_version,
+            app_version,
+        }
+    }
+
+    /// true si no se pudo extraer ningún dato del dispositivo
+    pub fn is_empty(&self) -> bool {
+        *self == DeviceInfo::default()
+    }
+}
+
+/// Extractor: reutiliza el DeviceInfo que ya haya parseado otro middleware (logging) si existe
+impl FromRequest for DeviceInfo {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(device_info(req)))
+    }
+}
+
+/// Obtiene el DeviceInfo del request, parseándolo y guardándolo en extensions la primera vez
+pub fn device_info(req: &HttpRequest) -> DeviceInfo {
+    if let Some(info) = req.extensions().get::<DeviceInfo>() {
+        return info.clone();
+    }
+    let info = DeviceInfo::from_headers(req.headers());
+    req.extensions_mut().insert(info.clone());
+    info
+}
+
+/// Parsea valores como "iOS 17.1.2", "ios/17.1", "Android 14" o "android"
+fn parse_os(value: &str) -> (String, Option<String>) {
+    let value = value.trim();
+    let split_at = value
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (name, version) = value.split_at(split_at);
+    let name = name.trim_matches(|c: char| c.is_whitespace() || c == '/' || c == '_' || c == '-');
+    let name = if name.is_empty() { value } else { name };
+    (normalize_os_name(name), normalize_version(version))
+}
+
+fn normalize_os_name(name: &str) -> String {
+    match name.to_ascii_lowercase().replace(' ', "").as_str() {
+        "ios" | "iphoneos" | "ipados" | "cpuiphoneos" | "cpuos" => "iOS".to_string(),
+        "android" => "Android".to_string(),
+        "macos" | "macosx" | "osx" => "macOS".to_string(),
+        "windows" => "Windows".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Normaliza "17_1_2" -> "17.1.2"; descarta cualquier sufijo que no sea parte de la versión
+fn normalize_version(version: &str) -> Option<String> {
+    let version: String = version
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '_')
+        .map(|c| if c == '_' { '.' } else { c })
+        .collect();
+    let version = version.trim_matches('.');
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+fn device_type_from_model(model: &str) -> Option<DeviceType> {
+    let lower = model.to_ascii_lowercase();
+    if lower.starts_with("ipad") {
+        Some(DeviceType::Tablet)
+    } else if lower.starts_with("iphone") || lower.starts_with("ipod") {
+        Some(DeviceType::Phone)
+    } else {
+        None
+    }
+}
+
+/// Extrae la información del dispositivo de los User-Agent que envían las apps y sus WebViews:
+/// - iOS app:     "Goil/3.4.0 (iPhone14,2; iOS 17.1.2; Scale/3.00)"
+/// - iOS nativo:  "Goil/120 CFNetwork/1485 Darwin/23.1.0"
+/// - iOS WebView: "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) ..."
+/// - Android:     "Dalvik/2.1.0 (Linux; U; Android 14; Pixel 8 Build/UD1A.230803.041)"
+/// - Android Web: "Mozilla/5.0 (Linux; Android 13; SM-S911B) ... Mobile Safari/537.36"
+fn parse_user_agent(ua: &str) -> DeviceInfo {
+    let mut info = DeviceInfo::default();
+    let (products, comments) = split_user_agent(ua);
+
+    for comment in &comments {
+        // El modelo de Android va en el mismo comentario, después del segmento "Android X"
+        let mut after_android = false;
+
+        for segment in comment.split(';').map(str::trim) {
+            let lower = segment.to_ascii_lowercase();
+
+            if lower.starts_with("iphone") || lower.starts_with("ipad") || lower.starts_with("ipod") {
+                info.model.get_or_insert_with(|| segment.to_string());
+                info.device_type.get_or_insert(
+                    if lower.starts_with("ipad") { DeviceType::Tablet } else { DeviceType::Phone },
+                );
+                info.os_name = Some("iOS".to_string());
+            } else if let Some(idx) = lower.find("cpu iphone os ").map(|i| i + 14)
+                .or_else(|| lower.find("cpu os ").map(|i| i + 7))
+            {
+                info.os_name = Some("iOS".to_string());
+                info.os_version = normalize_version(&segment[idx..]);
+            } else if lower.starts_with("ios ") || lower.starts_with("ipados ") || lower.starts_with("android") {
+                let (name, version) = parse_os(segment);
+                after_android = name == "Android";
+                info.os_name = Some(name);
+                info.os_version = version;
+            } else if after_android && info.model.is_none() {
+                let model = segment.split(" Build/").next().unwrap_or("").trim();
+                if is_android_model(model) {
+                    info.model = Some(model.to_string());
+                }
+            }
+        }
+    }
+
+    let mut is_browser = false;
+    let mut is_native_android = false;
+    let mut has_mobile_token = false;
+    for (name, version) in &products {
+        let lower = name.to_ascii_lowercase();
+        match lower.as_str() {
+            "mozilla" => is_browser = true,
+            "dalvik" | "okhttp" => is_native_android = true,
+            "mobile" => has_mobile_token = true,
+            "darwin" if info.os_name.is_none() => {
+                // Darwin 23.x -> iOS 17, Darwin 24.x -> iOS 18 (solo podemos inferir la versión mayor)
+                let ios_major = version
+                    .split('.')
+                    .next()
+                    .and_then(|m| m.parse::<u32>().ok())
+                    .filter(|m| *m > 6)
+                    .map(|m| (m - 6).to_string());
+                info.os_name = Some("iOS".to_string());
+                info.os_version = ios_major;
+            }
+            _ => {}
+        }
+        if info.app_version.is_none()
+            && !NON_APP_PRODUCTS.contains(&lower.as_str())
+            && version.starts_with(|c: char| c.is_ascii_digit())
+            && version.contains('.')
+        {
+            info.app_version = Some(version.to_string());
+        }
+    }
+
+    // Chrome en Android marca los móviles con "Mobile"; sin él es una tablet
+    if info.device_type.is_none() && is_browser && info.os_name.as_deref() == Some("Android") {
+        info.device_type = Some(if has_mobile_token { DeviceType::Phone } else { DeviceType::Tablet });
+    }
+    // La app nativa de Android (Dalvik/okhttp) no indica si es una tablet: se asume teléfono, salvo que
+    // la app lo indique con `x-client-device-type`
+    if info.device_type.is_none() && is_native_android {
+        info.device_type = Some(DeviceType::Phone);
+    }
+
+    info
+}
+
+/// Descarta los marcadores que acompañan al modelo en los User-Agent de Android:
+/// "K" (modelo congelado de Chrome), "wv" (WebView), "U", "Mobile" y locales como "es-ES"
+fn is_android_model(segment: &str) -> bool {
+    let is_locale = segment.len() == 5
+        && segment.as_bytes()[2] == b'-'
+        && segment.chars().filter(|c| *c != '-').all(|c| c.is_ascii_alphabetic());
+    !segment.is_empty() && !is_locale && !matches!(segment, "K" | "wv" | "U" | "Linux" | "Mobile")
+}
+
+/// Separa el User-Agent en productos ("Nombre/versión") y comentarios (texto entre paréntesis)
+fn split_user_agent(ua: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut products = Vec::new();
+    let mut comments = Vec::new();
+    let mut rest = ua;
+
+    while !rest.is_empty() {
+        if let Some(start) = rest.find('(') {
+            collect_products(&rest[..start], &mut products);
+            let end = rest[start..].find(')').map(|e| start + e).unwrap_or(rest.len());
+            comments.push(rest[start + 1..end].to_string());
+            rest = rest.get(end + 1..).unwrap_or("");
+        } else {
+            collect_products(rest, &mut products);
+            break;
+        }
+    }
+
+    (products, comments)
+}
+
+fn collect_products(text: &str, products: &mut Vec<(String, String)>) {
+    for token in text.split_whitespace() {
+        match token.split_once('/') {
+            Some((name, version)) => products.push((name.to_string(), version.to_string())),
+            None => products.push((token.to_string(), String::new())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn parses_ios_app_user_agent() {
+        let info = DeviceInfo::from_headers(&headers(&[
+            ("user-agent", "Goil/3.4.0 (iPhone14,2; iOS 17.1.2; Scale/3.00)"),
+        ]));
+        assert_eq!(info.device_type, Some(DeviceType::Phone));
+        assert_eq!(info.model.as_deref(), Some("iPhone14,2"));
+        assert_eq!(info.os_name.as_deref(), Some("iOS"));
+        assert_eq!(info.os_version.as_deref(), Some("17.1.2"));
+        assert_eq!(info.app_version.as_deref(), Some("3.4.0"));
+    }
+
+    #[test]
+    fn parses_ios_webview_user_agent() {
+        let info = DeviceInfo::from_headers(&headers(&[(
+            "user-agent",
+            "Mozilla/5.0 (iPad; CPU OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148",
+        )]));
+        assert_eq!(info.device_type, Some(DeviceType::Tablet));
+        assert_eq!(info.model.as_deref(), Some("iPad"));
+        assert_eq!(info.os_name.as_deref(), Some("iOS"));
+        assert_eq!(info.os_version.as_deref(), Some("17.1"));
+        assert_eq!(info.app_version, None);
+    }
+
+    #[test]
+    fn infers_ios_major_version_from_darwin() {
+        let info = DeviceInfo::from_headers(&headers(&[
+            ("user-agent", "Goil/120 CFNetwork/1485 Darwin/23.1.0"),
+        ]));
+        assert_eq!(info.os_name.as_deref(), Some("iOS"));
+        assert_eq!(info.os_version.as_deref(), Some("17"));
+        // "120" es el número de build, no la versión de la app
+        assert_eq!(info.app_version, None);
+    }
+
+    #[test]
+    fn parses_android_dalvik_user_agent() {
+        let info = DeviceInfo::from_headers(&headers(&[(
+            "user-agent",
+            "Dalvik/2.1.0 (Linux; U; Android 14; Pixel 8 Build/UD1A.230803.041)",
+        )]));
+        assert_eq!(info.os_name.as_deref(), Some("Android"));
+        assert_eq!(info.os_version.as_deref(), Some("14"));
+        assert_eq!(info.model.as_deref(), Some("Pixel 8"));
+        assert_eq!(info.device_type, Some(DeviceType::Phone));
+        assert_eq!(info.app_version, None);
+    }
+
+    #[test]
+    fn parses_android_chrome_user_agent() {
+        let phone = DeviceInfo::from_headers(&headers(&[(
+            "user-agent",
+            "Mozilla/5.0 (Linux; Android 13; SM-S911B) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Mobile Safari/537.36",
+        )]));
+        assert_eq!(phone.os_name.as_deref(), Some("Android"));
+        assert_eq!(phone.os_version.as_deref(), Some("13"));
+        assert_eq!(phone.model.as_deref(), Some("SM-S911B"));
+        assert_eq!(phone.device_type, Some(DeviceType::Phone));
+
+        let tablet = DeviceInfo::from_headers(&headers(&[(
+            "user-agent",
+            "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36",
+        )]));
+        assert_eq!(tablet.model, None);
+        assert_eq!(tablet.device_type, Some(DeviceType::Tablet));
+    }
+
+    #[test]
+    fn explicit_headers_take_precedence_over_user_agent() {
+        let info = DeviceInfo::from_headers(&headers(&[
+            ("user-agent", "Goil/3.4.0 (iPhone14,2; iOS 17.1.2; Scale/3.00)"),
+            ("x-client-device", "iPhone15,3"),
+            ("x-client-os", "ios"),
+            ("x-app-version", "3.5.0"),
+        ]));
+        assert_eq!(info.model.as_deref(), Some("iPhone15,3"));
+        assert_eq!(info.os_name.as_deref(), Some("iOS"));
+        // x-client-os sin versión: se completa con la del User-Agent
+        assert_eq!(info.os_version.as_deref(), Some("17.1.2"));
+        assert_eq!(info.app_version.as_deref(), Some("3.5.0"));
+    }
+
+    #[test]
+    fn parses_client_os_header_formats() {
+        assert_eq!(parse_os("Android 14"), ("Android".to_string(), Some("14".to_string())));
+        assert_eq!(parse_os("ios/17.1"), ("iOS".to_string(), Some("17.1".to_string())));
+        assert_eq!(parse_os("android"), ("Android".to_string(), None));
+        assert_eq!(parse_os("HarmonyOS"), ("HarmonyOS".to_string(), None));
+    }
+
+    #[test]
+    fn empty_headers_yield_empty_info() {
+        assert!(DeviceInfo::from_headers(&HeaderMap::new()).is_empty());
+    }
+}
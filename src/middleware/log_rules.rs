@@ -0,0 +1,156 @@
+use serde_json::Value;
+
+use crate::config::AccessLogConfig;
+
+/// Reglas de logging de un grupo de rutas (por prefijo del path), con los valores ya resueltos
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteLogRule {
+    /// Prefijo del path al que aplica ("/" = todas las rutas)
+    pub path: String,
+    /// false = no se registra nada de estas rutas (ni siquiera errores)
+    pub enabled: bool,
+    /// Fracción de respuestas 2xx/3xx que se registran (0.0 - 1.0); 4xx y 5xx se registran siempre
+    pub sample_rate: f64,
+    /// Incluir el body de la respuesta en el log
+    pub include_body: bool,
+    /// Tamaño máximo del body registrado (JSON serializado); se trunca al superarlo
+    pub max_body_bytes: usize,
+}
+
+/// Decisión de logging para una respuesta concreta
+#[derive(Debug, PartialEq)]
+pub enum LogDecision {
+    Skip,
+    Log { include_body: bool, max_body_bytes: usize },
+}
+
+/// Reglas por ruta; se aplica la de prefijo más largo que coincida
+#[derive(Clone, Debug)]
+pub struct LogRules {
+    rules: Vec<RouteLogRule>,
+}
+
+impl LogRules {
+    /// Las tasas de muestreo ya están validadas en `AppConfig::validate`
+    pub fn new(default_rule: RouteLogRule, mut routes: Vec<RouteLogRule>) -> Self {
+        routes.push(RouteLogRule { path: "/".to_string(), ..default_rule });
+        // Prefijos más largos primero
+        routes.sort_by_key(|rule| std::cmp::Reverse(rule.path.len()));
+        Self { rules: routes }
+    }
+
+    /// Crea las reglas de `AppConfig::access_log` (recargable): la regla por defecto y las reglas por ruta,
+    /// que toman de la regla por defecto los campos que no definen
+    pub fn from_config(config: &AccessLogConfig) -> Self {
+        let default_rule = RouteLogRule {
+            path: "/".to_string(),
+            enabled: true,
+            sample_rate: config.sample_rate_2xx,
+            include_body: config.include_response_body,
+            max_body_bytes: config.max_body_bytes,
+        };
+        let routes = config
+            .routes
+            .iter()
+            .map(|route| RouteLogRule {
+                path: route.path.clone(),
+                enabled: route.enabled,
+                sample_rate: route.sample_rate.unwrap_or(default_rule.sample_rate),
+                include_body: route.include_body.unwrap_or(default_rule.include_body),
+                max_body_bytes: route.max_body_bytes.unwrap_or(default_rule.max_body_bytes),
+            })
+            .collect();
+
+        Self::new(default_rule, routes)
+    }
+
+    fn rule_for(&self, path: &str) -> &RouteLogRule {
+        self.rules
+            .iter()
+            .find(|rule| path_matches(&rule.path, path))
+            .unwrap_or_else(|| self.rules.last().expect("default rule always present"))
+    }
+
+    /// La ruta no se registra nunca (se puede omitir todo el trabajo de logging)
+    pub fn is_disabled(&self, path: &str) -> bool {
+        !self.rule_for(path).enabled
+    }
+
+    /// Decide si registrar la respuesta; `sample` es un valor aleatorio en [0, 1)
+    pub fn decide(&self, path: &str, status: u16, sample: f64) -> LogDecision {
+        let rule = self.rule_for(path);
+        if !rule.enabled || (status < 400 && sample >= rule.sample_rate) {
+            return LogDecision::Skip;
+        }
+        LogDecision::Log { include_body: rule.include_body, max_body_bytes: rule.max_body_bytes }
+    }
+}
+
+/// "/health" coincide con "/health" y "/health/ready", pero no con "/healthz"
+fn path_matches(prefix: &str, path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    prefix.is_empty()
+        || path == prefix
+        || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Trunca el body si su JSON supera `max_bytes`; devuelve si se truncó
+pub fn truncate_body(body: &mut Value, max_bytes: usize) -> bool {
+    let serialized = match &*body {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if serialized.len() <= max_bytes {
+        return false;
+    }
+
+    let mut end = max_bytes;
+    while !serialized.is_char_boundary(end) {
+        end -= 1;
+    }
+    *body = Value::String(format!("{}…", &serialized[..end]));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: &str, sample_rate: f64) -> RouteLogRule {
+        RouteLogRule { path: path.to_string(), enabled: true, sample_rate, include_body: true, max_body_bytes: 10 }
+    }
+
+    #[test]
+    fn samples_success_but_always_logs_errors() {
+        let rules = LogRules::new(
+            rule("/", 1.0),
+            vec![
+                RouteLogRule { include_body: false, ..rule("/api/v2/notification", 0.1) },
+                RouteLogRule { enabled: false, ..rule("/health", 1.0) },
+            ],
+        );
+
+        assert_eq!(rules.decide("/api/v2/notification/1/me", 200, 0.5), LogDecision::Skip);
+        assert_eq!(
+            rules.decide("/api/v2/notification/1/me", 200, 0.05),
+            LogDecision::Log { include_body: false, max_body_bytes: 10 }
+        );
+        assert_eq!(
+            rules.decide("/api/v2/notification/1/me", 500, 0.99),
+            LogDecision::Log { include_body: false, max_body_bytes: 10 }
+        );
+        assert_eq!(rules.decide("/health/ready", 503, 0.0), LogDecision::Skip);
+        assert!(!rules.is_disabled("/healthz"));
+        assert_eq!(rules.decide("/other", 200, 0.99), LogDecision::Log { include_body: true, max_body_bytes: 10 });
+    }
+
+    #[test]
+    fn truncates_on_char_boundary() {
+        let mut body = serde_json::json!({ "title": "ñññññ" });
+        assert!(truncate_body(&mut body, 12));
+        assert_eq!(body, Value::String("{\"title\":\"ñ…".to_string()));
+
+        let mut small = serde_json::json!({ "a": 1 });
+        assert!(!truncate_body(&mut small, 100));
+    }
+}
@@ -4,14 +4,22 @@ use actix_web::{
     Error,
 };
 use actix_web::body::MessageBody;
+use actix_web::HttpMessage; // para extensions_mut()
 use futures_util::future::LocalBoxFuture;
 use serde_json::{json, Value};
 use std::{
+    cell::RefCell,
     future::{ready, Ready},
     rc::Rc,
     time::SystemTime,
 };
 use std::sync::Arc;
+use crate::config::{AppConfig, ConfigHandle};
+
+use crate::logging::ACCESS_LOG_TARGET;
+use crate::middleware::log_rules::{truncate_body, LogDecision, LogRules};
+use crate::middleware::redaction::Redactor;
+use crate::types::{DeviceInfo, RequestId};
 
 /// Configuración para el middleware de logging
 #[derive(Clone)]
@@ -19,33 +27,45 @@ pub struct LoggingConfig {
     pub hostname: String,
     pub loki_url: String,
     pub service_name: String,
+    /// Redacción de headers, query y bodies antes de registrar nada
+    pub redactor: Arc<Redactor>,
+    /// Configuración recargable: muestreo, inclusión del body y truncado (`access_log`)
+    pub app_config: ConfigHandle,
 }
 
 impl LoggingConfig {
-    /// Crea una nueva configuración desde variables de entorno o valores por defecto
-    pub fn from_env() -> Self {
-        let hostname = std::env::var("HOSTNAME")
-            .or_else(|_| hostname::get()
-                .ok()
-                .and_then(|h| h.into_string().ok())
-                .ok_or(()))
-            .unwrap_or_else(|_| "unknown".to_string());
-        
-        let loki_url = std::env::var("LOKI_URL")
-            .unwrap_or_else(|_| "https://gobs.goil.app/loki/loki".to_string());
-        
-        let service_name = std::env::var("SERVICE_NAME")
-            .unwrap_or_else(|_| "server-notifications".to_string());
-        
+    /// Crea la configuración desde la sección `[logging]`; sin `logging.hostname` se usa el del sistema
+    pub fn new(app_config: ConfigHandle) -> Self {
+        let current = app_config.current();
+        let logging = &current.logging;
+        let hostname = logging.hostname.clone()
+            .or_else(|| hostname::get().ok().and_then(|h| h.into_string().ok()))
+            .unwrap_or_else(|| "unknown".to_string());
+
         Self {
             hostname,
-            loki_url,
-            service_name,
+            loki_url: logging.loki.url.clone(),
+            service_name: logging.service_name.clone(),
+            redactor: Arc::new(Redactor::from_config(&logging.redaction)),
+            app_config,
         }
     }
+
+    /// Labels del stream de Loki, comunes a los logs de la aplicación y a los de acceso
+    /// (`job` y `service` se mantienen por compatibilidad con los dashboards existentes)
+    pub fn loki_labels(&self) -> Vec<(String, String)> {
+        vec![
+            ("service_name".to_string(), self.service_name.clone()),
+            ("job".to_string(), self.service_name.clone()),
+            ("service".to_string(), self.service_name.clone()),
+            ("host".to_string(), self.hostname.clone()),
+        ]
+    }
 }
 
 /// Middleware de logging estructurado en formato JSON compatible con Grafana
+/// Cada línea se emite como evento de tracing (target `access_log`); la capa `logging::LogSinks`
+/// la envía a Loki y/o al fichero junto con el resto de logs de la aplicación
 pub struct StructuredLogging {
     config: LoggingConfig,
 }
@@ -71,14 +91,10 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        // Crear cliente HTTP reutilizable para enviar logs a Loki
-        let client = Arc::new(reqwest::Client::new());
-        let config = self.config.clone();
-        
         ready(Ok(StructuredLoggingMiddleware {
             service: Rc::new(service),
-            config,
-            client,
+            config: self.config.clone(),
+            log_rules: RefCell::new(None),
         }))
     }
 }
@@ -86,7 +102,23 @@ where
 pub struct StructuredLoggingMiddleware<S> {
     service: Rc<S>,
     config: LoggingConfig,
-    client: Arc<reqwest::Client>,
+    /// Reglas construidas para la configuración en uso (por worker); se reconstruyen tras una recarga
+    log_rules: RefCell<Option<(Arc<AppConfig>, Rc<LogRules>)>>,
+}
+
+impl<S> StructuredLoggingMiddleware<S> {
+    fn current_log_rules(&self) -> Rc<LogRules> {
+        let app_config = self.config.app_config.current();
+        let mut cached = self.log_rules.borrow_mut();
+        match cached.as_ref() {
+            Some((version, rules)) if Arc::ptr_eq(version, &app_config) => rules.clone(),
+            _ => {
+                let rules = Rc::new(LogRules::from_config(&app_config.access_log));
+                *cached = Some((app_config, rules.clone()));
+                rules
+            }
+        }
+    }
 }
 
 impl<S, B> Service<ServiceRequest> for StructuredLoggingMiddleware<S>
@@ -104,8 +136,7 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let service = self.service.clone();
-        // Clonar client y config antes del async move para evitar problemas de lifetime
-        let client = self.client.clone();
+        // Clonar config antes del async move para evitar problemas de lifetime
         let config = self.config.clone();
         let start_time = SystemTime::now();
 
@@ -113,31 +144,40 @@ where
         let method = req.method().to_string();
         let path = req.path();
         
-        // Omitir logging para endpoints de health check
-        if path == "/health" || path.starts_with("/health/") {
-            // Para health checks, simplemente pasar la request sin logging
+        // Omitir logging para las rutas desactivadas (por defecto los health checks)
+        let log_rules = self.current_log_rules();
+        if log_rules.is_disabled(path) {
+            // Simplemente pasar la request sin logging
             return Box::pin(async move {
                 let res = service.call(req).await?;
                 Ok(res.map_into_boxed_body())
             });
         }
         
+        let path = path.to_string();
         let query_string = req.query_string();
+        let redactor = config.redactor.clone();
         let full_path = if query_string.is_empty() {
-            path.to_string()
+            path.clone()
         } else {
-            format!("{}?{}", path, query_string)
+            format!("{}?{}", path, redactor.query(query_string))
         };
 
-        // Capturar headers del request
+        // Capturar headers del request (redactados: los tokens nunca se registran)
         let mut request_headers = serde_json::Map::new();
         for (name, value) in req.headers().iter() {
             let header_name = name.as_str().to_lowercase();
-            if let Ok(header_value) = value.to_str() {
-                request_headers.insert(header_name, Value::String(header_value.to_string()));
+            if let Some(header_value) = value.to_str().ok().and_then(|v| redactor.header_value(&header_name, v)) {
+                request_headers.insert(header_name, Value::String(header_value));
             }
         }
 
+        let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+
+        // Información del dispositivo: se guarda en extensions para que los handlers la reutilicen
+        let device = DeviceInfo::from_headers(req.headers());
+        req.extensions_mut().insert(device.clone());
+
         // Usar la configuración pasada al middleware (no leer de env cada vez)
         let hostname = config.hostname.clone();
         let service_name = config.service_name.clone();
@@ -155,11 +195,18 @@ where
             // Capturar información del response
             let status_code = res.status().as_u16();
 
+            // Muestreo de respuestas correctas según la regla de la ruta (los errores se registran siempre)
+            let (include_body, max_body_bytes) = match log_rules.decide(&path, status_code, rand::random::<f64>()) {
+                LogDecision::Skip => return Ok(res.map_into_boxed_body()),
+                LogDecision::Log { include_body, max_body_bytes } => (include_body, max_body_bytes),
+            };
+
             // Capturar TODOS los headers del response (incluyendo los que se añaden después)
             let mut response_headers = serde_json::Map::new();
             for (name, value) in res.headers().iter() {
                 let header_name = name.as_str().to_lowercase();
-                if let Ok(header_value) = value.to_str() {
+                if let Some(header_value) = value.to_str().ok().and_then(|v| redactor.header_value(&header_name, v)) {
+                    let header_value = header_value.as_str();
                     // Si el header ya existe, convertir a array para mantener todos los valores
                     if let Some(existing) = response_headers.get_mut(&header_name) {
                         match existing {
@@ -190,61 +237,33 @@ where
                 }
             }
 
-            // Convertir el response a BoxBody y leer el body
-            let res = res.map_into_boxed_body();
-            let (req_parts, res_body) = res.into_parts();
-            
-            // Extraer el body usando map_body
-            let (head, body) = res_body.into_parts();
-            let body_bytes = actix_web::body::to_bytes(body).await;
-            
-            let response_body: Value = match &body_bytes {
-                Ok(bytes) => {
-                    if bytes.is_empty() {
-                        Value::Null
-                    } else {
-                        // Intentar parsear como JSON, si falla usar el string
-                        match serde_json::from_slice::<Value>(bytes) {
-                            Ok(json) => {
-                                // Si es un objeto JSON, extraer solo el campo "data"
-                                if let Some(obj) = json.as_object() {
-                                    if let Some(data) = obj.get("data") {
-                                        data.clone()
-                                    } else {
-                                        // Si no tiene "data", devolver el objeto completo pero sin "timestamp"
-                                        let mut filtered = serde_json::Map::new();
-                                        for (key, value) in obj.iter() {
-                                            if key != "timestamp" {
-                                                filtered.insert(key.clone(), value.clone());
-                                            }
-                                        }
-                                        Value::Object(filtered)
-                                    }
-                                } else {
-                                    json
-                                }
-                            }
-                            Err(_) => {
-                                // Si no es JSON, intentar como string UTF-8
-                                match String::from_utf8(bytes.to_vec()) {
-                                    Ok(s) => Value::String(s),
-                                    Err(_) => Value::String(format!("<binary data: {} bytes>", bytes.len())),
-                                }
-                            }
-                        }
-                    }
+            // El body solo se lee (y se reconstruye la respuesta) si la regla de la ruta lo incluye
+            let (res, mut response_body) = if include_body {
+                let res = res.map_into_boxed_body();
+                let (req_parts, res_body) = res.into_parts();
+
+                // Extraer el body usando map_body
+                let (head, body) = res_body.into_parts();
+                let body_bytes = actix_web::body::to_bytes(body).await;
+                let response_body = match &body_bytes {
+                    Ok(bytes) => response_body_to_value(bytes),
+                    Err(_) => Value::Null,
+                };
+
+                // Reconstruir el response con el body leído
+                let body_bytes_final = body_bytes.unwrap_or_default();
+                let mut res_body_rebuilt = actix_web::HttpResponse::with_body(head.status(), BoxBody::new(body_bytes_final));
+                // Copiar headers del head original
+                for (name, value) in head.headers().iter() {
+                    res_body_rebuilt.headers_mut().insert(name.clone(), value.clone());
                 }
-                Err(_) => Value::Null,
+                (ServiceResponse::new(req_parts, res_body_rebuilt), response_body)
+            } else {
+                (res.map_into_boxed_body(), Value::Null)
             };
-
-            // Reconstruir el response con el body leído
-            let body_bytes_final = body_bytes.unwrap_or_default();
-            let mut res_body_rebuilt = actix_web::HttpResponse::with_body(head.status(), BoxBody::new(body_bytes_final));
-            // Copiar headers del head original
-            for (name, value) in head.headers().iter() {
-                res_body_rebuilt.headers_mut().insert(name.clone(), value.clone());
-            }
-            let res = ServiceResponse::new(req_parts, res_body_rebuilt);
+            // Redactar antes de truncar: las máscaras necesitan el JSON completo
+            redactor.body(&mut response_body);
+            let response_body_truncated = truncate_body(&mut response_body, max_body_bytes);
 
             // Determinar nivel de log basado en status code
             let level = if status_code >= 500 {
@@ -264,82 +283,126 @@ where
                 "hostname": hostname,
                 "pid": pid,
                 "level": level,
+                "requestId": request_id,
                 "http": {
                     "path": full_path,
                     "method": method,
                     "requestBody": {},
                     "statusCode": status_code,
                     "responseBody": response_body,
+                    "responseBodyTruncated": response_body_truncated,
                     "requestHeaders": request_headers,
                     "responseHeaders": response_headers,
                     "duration": duration_ms
                 },
+                "device": if device.is_empty() { Value::Null } else { json!(device) },
                 "msg": "",
                 "time": time,
                 "v": 0
             });
 
-            // Enviar directamente a Loki usando reqwest para controlar el formato exacto
+            // Formato exacto de la línea enviada a Loki y escrita en fichero
             let log_json = serde_json::to_string(&log_entry).unwrap_or_default();
             
-            // Print local para debugging
-            eprintln!("[logging] Sending log to Loki: {}", log_json);
-            
-            // Construir payload para Loki
-            // Usar el hostname que ya capturamos al inicio (consistente con el JSON del log)
-            // No obtenerlo de nuevo aquí para evitar inconsistencias
-            
-            let timestamp_ns = chrono::Utc::now().timestamp_nanos_opt()
-                .unwrap_or_else(|| chrono::Utc::now().timestamp() * 1_000_000_000);
-            
-            let loki_payload = json!({
-                "streams": [{
-                    "stream": {
-                        "job": "server-notifications",
-                        "service": "server-notifications",
-                        "host": hostname
-                    },
-                    "values": [[
-                        timestamp_ns.to_string(),
-                        log_json
-                    ]]
-                }]
-            });
-            
-            // Enviar a Loki en background (no bloquear la respuesta)
-            // Asegurar que la URL tenga /api/v1/push
-            let loki_url_final = if config.loki_url.ends_with("/api/v1/push") {
-                config.loki_url.clone()
-            } else if config.loki_url.ends_with("/loki") {
-                format!("{}/api/v1/push", config.loki_url)
-            } else {
-                format!("{}/loki/api/v1/push", config.loki_url)
-            };
-            
-            // Serializar el payload antes del spawn para evitar problemas de lifetime
-            let payload_json = serde_json::to_string(&loki_payload).unwrap_or_default();
-            
-            tokio::spawn(async move {
-                match client
-                    .post(&loki_url_final)
-                    .header("Content-Type", "application/json")
-                    .body(payload_json)
-                    .send()
-                    .await
-                {
-                    Ok(resp) => {
-                        if !resp.status().is_success() {
-                            eprintln!("[logging] Loki respondió con error: {} - {:?}", resp.status(), resp.text().await.ok());
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[logging] Error enviando log a Loki: {}", e);
-                    }
-                }
-            });
+            // La capa `LogSinks` la encola para Loki y/o el fichero (no bloquea la respuesta)
+            match level {
+                50 => tracing::error!(target: ACCESS_LOG_TARGET, line = %log_json),
+                40 => tracing::warn!(target: ACCESS_LOG_TARGET, line = %log_json),
+                _ => tracing::info!(target: ACCESS_LOG_TARGET, line = %log_json),
+            }
 
             Ok(res)
         })
     }
 }
 
+
+
+/// Body de la respuesta para el log: el campo "data" de `ApiResponse`, el JSON sin "timestamp" o el texto
+fn response_body_to_value(bytes: &[u8]) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+
+    // Intentar parsear como JSON, si falla usar el string
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(json) => {
+            // Si es un objeto JSON, extraer solo el campo "data"
+            if let Some(obj) = json.as_object() {
+                if let Some(data) = obj.get("data") {
+                    data.clone()
+                } else {
+                    // Si no tiene "data", devolver el objeto completo pero sin "timestamp"
+                    let mut filtered = serde_json::Map::new();
+                    for (key, value) in obj.iter() {
+                        if key != "timestamp" {
+                            filtered.insert(key.clone(), value.clone());
+                        }
+                    }
+                    Value::Object(filtered)
+                }
+            } else {
+                json
+            }
+        }
+        Err(_) => {
+            // Si no es JSON, intentar como string UTF-8
+            match String::from_utf8(bytes.to_vec()) {
+                Ok(s) => Value::String(s),
+                Err(_) => Value::String(format!("<binary data: {} bytes>", bytes.len())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+    use tracing_subscriber::layer::SubscriberExt;
+    use crate::infrastructure::external::loki::LokiShipper;
+    use crate::logging::LogSinks;
+    use crate::response::ApiResponse;
+
+    #[actix_web::test]
+    async fn tokens_and_pii_never_reach_the_log_shipper() {
+        let shipper = LokiShipper::unstarted(10);
+        let config = LoggingConfig {
+            hostname: "test".to_string(),
+            loki_url: "http://localhost:3100".to_string(),
+            service_name: "server-notifications".to_string(),
+            redactor: Arc::new(Redactor::from_config(&Default::default())),
+            app_config: ConfigHandle::new(AppConfig::default(), None),
+        };
+        let sinks = LogSinks::new(&config, Some(shipper.clone()), None);
+        let _subscriber = tracing::subscriber::set_default(tracing_subscriber::registry().with(sinks));
+        let app = test::init_service(
+            App::new()
+                .wrap(StructuredLogging::new(config))
+                .route("/n", web::get().to(|| async {
+                    HttpResponse::Ok()
+                        .insert_header(("set-cookie", "session=cookie-secret"))
+                        .json(ApiResponse::ok(serde_json::json!({
+                            "notification": { "title": "Hola Ana", "body": "Tu pedido 600123456" },
+                            "accountId": "acc-123"
+                        })))
+                })),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/n?token=query-secret&lang=es")
+            .insert_header(("authorization", "Bearer header-secret"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(res.status().is_success());
+
+        let lines = shipper.pending_lines();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        for secret in ["header-secret", "query-secret", "cookie-secret", "Hola Ana", "600123456", "acc-123"] {
+            assert!(!line.contains(secret), "'{}' leaked into log line: {}", secret, line);
+        }
+        assert!(line.contains("lang=es"));
+    }
+}
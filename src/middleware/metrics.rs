@@ -0,0 +1,49 @@
+use actix_web::{dev::ServiceRequest, dev::ServiceResponse, Error};
+use actix_web::body::BoxBody; // BoxBody: tipo único para el cuerpo de la respuesta que evita genéricos opacos en middlewares
+use actix_web::middleware::Next;
+use std::time::Instant;
+
+/// Middleware: registra la latencia de cada request por método, patrón de ruta y status
+/// Se usa el patrón (ej: "/api/v2/notification/{id}/me") para no crear una serie por ID
+pub async fn http_metrics(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let start = Instant::now();
+    let method = req.method().to_string();
+
+    // El patrón solo se conoce después del routing, que ocurre dentro de `next`
+    let result = next.call(req).await;
+    let (route, status) = match &result {
+        Ok(res) => (res.request().match_pattern(), res.status()),
+        Err(e) => (None, e.as_response_error().status_code()),
+    };
+    let route = route.unwrap_or_else(|| "unmatched".to_string());
+
+    crate::metrics::HTTP_REQUEST_DURATION
+        .with_label_values(&[method.as_str(), route.as_str(), status.as_str()])
+        .observe(start.elapsed().as_secs_f64());
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{middleware::from_fn, test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn records_latency_by_route_pattern() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(http_metrics))
+                .route("/metrics-test/{id}/me", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        )
+        .await;
+        test::call_service(&app, test::TestRequest::get().uri("/metrics-test/abc/me").to_request()).await;
+
+        let text = crate::metrics::render().unwrap();
+        assert!(text.contains(r#"route="/metrics-test/{id}/me""#));
+        assert!(!text.contains("/metrics-test/abc/me"));
+    }
+}
@@ -1,4 +1,11 @@
 pub mod platform;
 pub mod auth;
 pub mod session;
-pub mod logging;
\ No newline at end of file
+pub mod logging;
+pub mod device;
+pub mod admin;
+pub mod redaction;
+pub mod log_rules;
+pub mod metrics;
+pub mod trace_context;
+pub mod request_id;
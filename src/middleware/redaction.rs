@@ -0,0 +1,232 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use crate::config::RedactionConfig;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Segmento de un path JSON: `clave`, `*` (cualquier clave) o `**` (cualquier profundidad)
+/// Los arrays son transparentes: el segmento se aplica a cada elemento
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Any,
+    AnyDepth,
+}
+
+fn parse_path(path: &str) -> Vec<Segment> {
+    path.split('.')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| match s {
+            "*" => Segment::Any,
+            "**" => Segment::AnyDepth,
+            key => Segment::Key(key.to_string()),
+        })
+        .collect()
+}
+
+/// Redacción de secretos y datos personales antes de que un log salga del proceso
+///
+/// - Headers: los de la denylist se sustituyen por "[REDACTED]"; con allowlist solo se registran los permitidos
+/// - Query string: se ocultan los valores de los parámetros sensibles
+/// - Bodies JSON: los paths de `mask_paths` se ocultan y los de `hash_paths` se sustituyen por un hash
+///   estable, para poder correlacionar requests sin registrar el identificador
+#[derive(Clone, Debug)]
+pub struct Redactor {
+    denied_headers: HashSet<String>,
+    allowed_headers: Option<HashSet<String>>,
+    hashed_headers: HashSet<String>,
+    denied_query_params: HashSet<String>,
+    mask_paths: Vec<Vec<Segment>>,
+    hash_paths: Vec<Vec<Segment>>,
+    hash_salt: String,
+}
+
+impl Redactor {
+    /// Crea el redactor desde la sección `[logging.redaction]`
+    pub fn from_config(config: &RedactionConfig) -> Self {
+        let lowercase = |values: &[String]| values.iter().map(|v| v.trim().to_ascii_lowercase()).collect();
+        let paths = |values: &[String]| values.iter().map(|p| parse_path(p)).collect();
+
+        Self {
+            denied_headers: lowercase(&config.headers),
+            allowed_headers: config.allowed_headers.as_deref().map(lowercase),
+            hashed_headers: lowercase(&config.hash_headers),
+            denied_query_params: lowercase(&config.query_params),
+            mask_paths: paths(&config.mask_body_paths),
+            hash_paths: paths(&config.hash_body_paths),
+            hash_salt: config.hash_salt.expose().to_string(),
+        }
+    }
+
+    /// Valor a registrar para un header; None si no debe registrarse
+    pub fn header_value(&self, name: &str, value: &str) -> Option<String> {
+        let name = name.to_ascii_lowercase();
+        if self.allowed_headers.as_ref().is_some_and(|allowed| !allowed.contains(&name)) {
+            return None;
+        }
+        if self.denied_headers.contains(&name) {
+            return Some(REDACTED.to_string());
+        }
+        if self.hashed_headers.contains(&name) {
+            return Some(self.hash(value));
+        }
+        Some(value.to_string())
+    }
+
+    /// Oculta los valores de los parámetros sensibles de una query string
+    pub fn query(&self, query: &str) -> String {
+        query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if self.denied_query_params.contains(&decode_key(key)) => {
+                    format!("{}={}", key, REDACTED)
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Aplica las máscaras y los hashes a un body JSON
+    pub fn body(&self, value: &mut Value) {
+        for path in &self.mask_paths {
+            apply(value, path, &|_| Value::String(REDACTED.to_string()));
+        }
+        for path in &self.hash_paths {
+            apply(value, path, &|v| match v {
+                Value::Null => Value::Null,
+                Value::String(s) => Value::String(self.hash(s)),
+                other => Value::String(self.hash(&other.to_string())),
+            });
+        }
+    }
+
+    /// Hash estable (SHA-256 con sal, 16 caracteres hex) con prefijo "h:"
+    fn hash(&self, value: &str) -> String {
+        let digest = Sha256::new()
+            .chain_update(self.hash_salt.as_bytes())
+            .chain_update(value.as_bytes())
+            .finalize();
+        let hex: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+        format!("h:{}", hex)
+    }
+}
+
+/// Nombre de un parámetro de query decodificado (`%XX` y `+`) y en minúsculas
+fn decode_key(key: &str) -> String {
+    let bytes = key.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_ascii_lowercase()
+}
+
+fn apply(value: &mut Value, path: &[Segment], action: &dyn Fn(&Value) -> Value) {
+    let Some((segment, rest)) = path.split_first() else {
+        *value = action(value);
+        return;
+    };
+
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|item| apply(item, path, action)),
+        Value::Object(map) => match segment {
+            Segment::Key(key) => {
+                if let Some(child) = map.get_mut(key) {
+                    apply(child, rest, action);
+                }
+            }
+            Segment::Any => map.values_mut().for_each(|child| apply(child, rest, action)),
+            Segment::AnyDepth => {
+                // `**` cubre cero niveles (este objeto) y cualquier nivel por debajo
+                apply_object_level(map, rest, action);
+                map.values_mut().for_each(|child| apply(child, path, action));
+            }
+        },
+        _ => {}
+    }
+}
+
+fn apply_object_level(map: &mut serde_json::Map<String, Value>, rest: &[Segment], action: &dyn Fn(&Value) -> Value) {
+    let mut wrapper = Value::Object(std::mem::take(map));
+    apply(&mut wrapper, rest, action);
+    if let Value::Object(inner) = wrapper {
+        *map = inner;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor() -> Redactor {
+        Redactor {
+            denied_headers: ["authorization".to_string()].into(),
+            allowed_headers: None,
+            hashed_headers: ["x-client-id".to_string()].into(),
+            denied_query_params: ["token".to_string()].into(),
+            mask_paths: vec![parse_path("notification.title"), parse_path("**.phone")],
+            hash_paths: vec![parse_path("**.accountId")],
+            hash_salt: "salt".to_string(),
+        }
+    }
+
+    #[test]
+    fn redacts_and_hashes_headers() {
+        let r = redactor();
+        assert_eq!(r.header_value("Authorization", "Bearer abc").as_deref(), Some(REDACTED));
+        assert_eq!(r.header_value("user-agent", "okhttp").as_deref(), Some("okhttp"));
+        let hashed = r.header_value("x-client-id", "client-1").unwrap();
+        assert!(hashed.starts_with("h:") && !hashed.contains("client-1"));
+        assert_eq!(r.header_value("x-client-id", "client-1").unwrap(), hashed);
+
+        let allowlisted = Redactor { allowed_headers: Some(["user-agent".to_string()].into()), ..redactor() };
+        assert_eq!(allowlisted.header_value("x-client-os", "ios"), None);
+        assert_eq!(r.query("a=1&token=abc"), "a=1&token=[REDACTED]");
+    }
+
+    #[test]
+    fn redacts_query_params_regardless_of_case_and_encoding() {
+        let r = Redactor { denied_query_params: ["token".to_string(), "access_token".to_string()].into(), ..redactor() };
+        assert_eq!(r.query("Token=abc&page=2"), "Token=[REDACTED]&page=2");
+        assert_eq!(r.query("ACCESS_TOKEN=abc"), "ACCESS_TOKEN=[REDACTED]");
+        assert_eq!(r.query("access%5Ftoken=abc"), "access%5Ftoken=[REDACTED]");
+    }
+
+    #[test]
+    fn masks_json_paths_at_any_depth() {
+        let mut body = json!({
+            "notification": { "title": "Hola Ana", "body": "b" },
+            "accountId": "acc-1",
+            "items": [{ "phone": "+34600000000", "user": { "accountId": "acc-2", "phone": "+34611111111" } }]
+        });
+        redactor().body(&mut body);
+
+        assert_eq!(body["notification"]["title"], REDACTED);
+        assert_eq!(body["notification"]["body"], "b");
+        assert_eq!(body["items"][0]["phone"], REDACTED);
+        assert_eq!(body["items"][0]["user"]["phone"], REDACTED);
+        let text = body.to_string();
+        assert!(!text.contains("acc-1") && !text.contains("acc-2") && !text.contains("+346"));
+        assert!(body["accountId"].as_str().unwrap().starts_with("h:"));
+    }
+}
@@ -0,0 +1,77 @@
+use actix_web::body::BoxBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage};
+
+use crate::types::RequestId;
+
+pub static REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longitud máxima aceptada para un `x-request-id` recibido
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    /// ID del request en curso: disponible en cualquier código que se ejecute dentro del handler
+    /// (use cases, repositorios, clientes HTTP) sin tener que pasarlo por parámetro
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// ID del request en curso (None fuera de un request, ej: tareas en segundo plano)
+pub fn current() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Reutiliza el `x-request-id` del cliente si es válido (ASCII visible, hasta 128 caracteres) o genera uno nuevo
+fn resolve_request_id(req: &ServiceRequest) -> String {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| c.is_ascii_graphic()))
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Middleware: asigna el ID de correlación del request, lo guarda en extensions y lo devuelve en `x-request-id`
+pub async fn request_id(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let id = resolve_request_id(&req);
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut res = CURRENT_REQUEST_ID.scope(id.clone(), next.call(req)).await?;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{middleware::from_fn, test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn reuses_or_generates_request_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(request_id))
+                .route("/rid", web::get().to(|| async { HttpResponse::Ok().body(current().unwrap_or_default()) })),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/rid").insert_header((REQUEST_ID_HEADER, "support-123")).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "support-123");
+        assert_eq!(test::read_body(res).await, "support-123");
+
+        let req = test::TestRequest::get().uri("/rid").insert_header((REQUEST_ID_HEADER, "bad id")).to_request();
+        let res = test::call_service(&app, req).await;
+        let generated = res.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&generated).is_ok());
+        assert_eq!(test::read_body(res).await, generated);
+        assert_eq!(current(), None);
+    }
+}
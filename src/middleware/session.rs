@@ -4,6 +4,7 @@ use actix_web::middleware::Next;
 use actix_web::web;
 use actix_web::HttpMessage; // para extensions()
 use crate::infrastructure::services::AppServices;
+use crate::middleware::device::device_info;
 use crate::types::AuthContext;
 use crate::response::ApiResponse;
 
@@ -13,6 +14,12 @@ fn unauthorized(msg: &str) -> HttpResponse {
         .body(serde_json::to_string(&ApiResponse::<()>::error(msg)).unwrap())
 }
 
+fn forbidden(msg: &str) -> HttpResponse {
+    HttpResponse::Forbidden()
+        .content_type("application/json")
+        .body(serde_json::to_string(&ApiResponse::<()>::error(msg)).unwrap())
+}
+
 fn internal_error(msg: &str) -> HttpResponse {
     HttpResponse::InternalServerError()
         .content_type("application/json")
@@ -20,7 +27,9 @@ fn internal_error(msg: &str) -> HttpResponse {
 }
 
 /// Middleware: valida la sesión usando el use case de session
-/// Extrae AppServices desde app_data y usa get_session para validar la sesión
+/// Extrae AppServices desde app_data y usa get_session para validar la sesión.
+/// También resuelve la configuración del tenant (`TenantConfig`): comprueba que el SO del cliente está
+/// permitido para el business y la deja en extensions junto con el idioma (el de la sesión o el del tenant)
 pub async fn session_guard(
     req: ServiceRequest,
     next: Next<BoxBody>,
@@ -56,20 +65,37 @@ pub async fn session_guard(
         ));
     }
     
-    // Consultar la sesión en MongoDB usando el use case (filtra por sessionId y businessId)
-    match services.session.get_session.execute(&session_id, &business_id).await {
-        Ok(session) => {
-            // Guardar el language de la sesión en extensions para que los handlers lo usen
-            req.extensions_mut().insert(session.language);
-            // Sesión válida, continuar
-            let res = next.call(req).await?.map_into_boxed_body();
-            Ok(res)
-        }
-        Err(_) => {
-            // Sesión no encontrada o inválida
-            Ok(req.into_response(
-                unauthorized("Invalid session").map_into_boxed_body(),
-            ))
-        }
+    // Consultar la sesión en MongoDB (filtra por sessionId y businessId) y la configuración del tenant en paralelo
+    let (session_result, tenant) = tokio::join!(
+        services.session.get_session.execute(&session_id, &business_id),
+        services.business.get_tenant_config.execute(&business_id),
+    );
+
+    let Ok(session) = session_result else {
+        // Sesión no encontrada o inválida
+        return Ok(req.into_response(
+            unauthorized("Invalid session").map_into_boxed_body(),
+        ));
+    };
+
+    let device = device_info(req.request());
+    if !tenant.allows_platform(device.os_name.as_deref()) {
+        tracing::warn!(
+            business_id = %business_id,
+            os = device.os_name.as_deref().unwrap_or("unknown"),
+            "Platform not allowed for business"
+        );
+        return Ok(req.into_response(
+            forbidden("Platform not Authorized").map_into_boxed_body(),
+        ));
     }
-}
\ No newline at end of file
+
+    // Guardar el language (de la sesión o, si no tiene, el del tenant) y el tenant para los handlers
+    let language = session.language.unwrap_or_else(|| tenant.default_language.clone());
+    req.extensions_mut().insert(language);
+    req.extensions_mut().insert(tenant);
+
+    // Sesión válida, continuar
+    let res = next.call(req).await?.map_into_boxed_body();
+    Ok(res)
+}
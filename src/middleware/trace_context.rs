@@ -0,0 +1,46 @@
+use actix_web::body::BoxBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{Error, HttpMessage};
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::telemetry::extract_context;
+use crate::types::RequestId;
+
+/// Middleware: abre el span raíz del request y continúa la traza del `traceparent` entrante (si lo hay)
+/// Los spans de use cases, Mongo, GetStream y cola cuelgan de este span
+pub async fn trace_context(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    // El ID de correlación queda en el span: cada evento de tracing del request lo incluye
+    let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone()).unwrap_or_default();
+    let method = req.method().clone();
+    // El nombre usa el patrón de la ruta (baja cardinalidad); hasta el routing solo se conoce el método
+    let span = tracing::info_span!(
+        "http_request",
+        otel.name = %method,
+        otel.kind = "server",
+        http.method = %req.method(),
+        http.target = %req.path(),
+        http.status_code = tracing::field::Empty,
+        request_id = %request_id,
+    );
+    if let Err(e) = span.set_parent(extract_context(req.headers())) {
+        tracing::debug!(error = %e, "Could not continue incoming trace");
+    }
+
+    let result = next.call(req).instrument(span.clone()).await;
+    let status = match &result {
+        Ok(res) => {
+            if let Some(pattern) = res.request().match_pattern() {
+                span.record("otel.name", format!("{} {}", method, pattern));
+            }
+            res.status()
+        }
+        Err(e) => e.as_response_error().status_code(),
+    };
+    span.record("http.status_code", status.as_u16());
+    result
+}
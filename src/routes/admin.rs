@@ -0,0 +1,38 @@
+use actix_web::web;
+use actix_web::dev::HttpServiceFactory;
+use actix_web::middleware::from_fn;
+use crate::middleware::admin::admin_guard;
+use crate::controllers::AdminController;
+use crate::controllers::admin::resolve_storage_key::ResolveStorageKeyQuery;
+use crate::mappers::feature_flag::FeatureFlagUpdateRequest;
+
+async fn resolve_storage_key(
+    services: web::Data<crate::infrastructure::services::AppServices>,
+    query: web::Query<ResolveStorageKeyQuery>,
+) -> impl actix_web::Responder {
+    AdminController::resolve_storage_key(services, query.into_inner()).await
+}
+
+async fn list_feature_flags(
+    services: web::Data<crate::infrastructure::services::AppServices>,
+) -> impl actix_web::Responder {
+    AdminController::list_feature_flags(services).await
+}
+
+async fn toggle_feature_flag(
+    services: web::Data<crate::infrastructure::services::AppServices>,
+    path: web::Path<String>,
+    body: web::Json<FeatureFlagUpdateRequest>,
+) -> impl actix_web::Responder {
+    AdminController::toggle_feature_flag(services, path.into_inner(), body.into_inner()).await
+}
+
+/// Endpoints internos de operación (`[admin]`: habilitados con `ADMIN_API_ENABLED` y protegidos con `ADMIN_API_TOKEN`)
+pub fn router() -> impl HttpServiceFactory {
+    web::scope("/api/v2/admin")
+        .app_data(super::notification::json_config())
+        .wrap(from_fn(admin_guard))
+        .route("/storage/resolve", web::get().to(resolve_storage_key))
+        .route("/flags", web::get().to(list_feature_flags))
+        .route("/flags/{key}", web::patch().to(toggle_feature_flag))
+}
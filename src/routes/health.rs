@@ -1,15 +1,29 @@
 use actix_web::{web, HttpResponse, Responder, Scope};
 use serde::Serialize;
+use crate::infrastructure::services::AppServices;
 use crate::response::ApiResponse;
 
 #[derive(Serialize)]
 struct HealthData { status: &'static str }
 
+/// Liveness: el proceso responde (no comprueba dependencias, para no reiniciar por caídas ajenas)
 async fn health() -> impl Responder {
     HttpResponse::Ok().json(ApiResponse::ok(HealthData { status: "ok" }))
 }
 
-pub fn router() -> Scope {
-    web::scope("/health").route("", web::get().to(health))
+/// Readiness: 200 si todas las dependencias están disponibles, 503 con el detalle si no
+async fn ready(services: web::Data<AppServices>) -> impl Responder {
+    let report = services.readiness.check().await;
+    if report.is_ready() {
+        HttpResponse::Ok().json(ApiResponse::ok(report))
+    } else {
+        HttpResponse::ServiceUnavailable().json(ApiResponse::ok(report))
+    }
 }
 
+pub fn router() -> Scope {
+    web::scope("/health")
+        .route("", web::get().to(health))
+        .route("/live", web::get().to(health))
+        .route("/ready", web::get().to(ready))
+}
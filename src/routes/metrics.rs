@@ -0,0 +1,22 @@
+use actix_web::dev::HttpServiceFactory;
+use actix_web::middleware::from_fn;
+use actix_web::{web, HttpResponse, Responder};
+use crate::middleware::admin::admin_guard;
+use crate::response::ApiResponse;
+
+async fn metrics() -> impl Responder {
+    match crate::metrics::render() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e)),
+    }
+}
+
+/// Métricas Prometheus: exponen rutas y volumen de tráfico, por eso van tras `admin_guard` como la API admin
+/// (el scraper envía `x-admin-token`)
+pub fn router() -> impl HttpServiceFactory {
+    web::scope("/metrics")
+        .wrap(from_fn(admin_guard))
+        .route("", web::get().to(metrics))
+}
@@ -1,10 +1,12 @@
-use actix_web::{web, HttpRequest};
+use actix_web::{web, HttpRequest, HttpResponse};
 use actix_web::dev::HttpServiceFactory;
 use actix_web::middleware::from_fn;
 use crate::middleware::platform::mobile_platform_guard;
 use crate::middleware::auth::auth_guard;
 use crate::middleware::session::session_guard;
 use crate::controllers::NotificationController;
+use crate::mappers::notification::NotificationEventRequest;
+use crate::response::ApiResponse;
 
 async fn get_notification(
     req: HttpRequest,
@@ -38,11 +40,33 @@ async fn get_notification(
     NotificationController::get_notification(req, services, id, business_ids).await
 }
 
+async fn track_event(
+    req: HttpRequest,
+    services: web::Data<crate::infrastructure::services::AppServices>,
+    path: web::Path<String>,
+    body: web::Json<NotificationEventRequest>,
+) -> impl actix_web::Responder {
+    NotificationController::track_event(req, services, path.into_inner(), body.into_inner()).await
+}
+
+/// Errores de deserialización del body con el mismo formato que el resto de la API
+pub(super) fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(4096)
+        .error_handler(|err, _req| {
+            let msg = format!("Invalid request body: {}", err);
+            let resp = HttpResponse::BadRequest().json(ApiResponse::<()>::error(msg));
+            actix_web::error::InternalError::from_response(err, resp).into()
+        })
+}
+
 pub fn router() -> impl HttpServiceFactory {
     web::scope("/api/v2/notification")
+        .app_data(json_config())
         .wrap(from_fn(session_guard))
         .wrap(from_fn(auth_guard))
         .wrap(from_fn(mobile_platform_guard))
         .route("/{id}/me", web::get().to(get_notification))
+        .route("/{id}/events", web::post().to(track_event))
 }
 
@@ -0,0 +1,121 @@
+use actix_web::http::header::HeaderMap;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::global;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::config::TelemetryConfig;
+
+/// Crea el proveedor de trazas OTLP y registra el propagador W3C (`traceparent`)
+/// Devuelve None si no hay endpoint configurado; el proveedor se debe cerrar al terminar para enviar lo pendiente
+pub fn init_tracer_provider(config: &TelemetryConfig) -> Result<Option<SdkTracerProvider>, String> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let Some(endpoint) = &config.endpoint else {
+        return Ok(None);
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpBinary)
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .map_err(|e| format!("OTLP exporter error: {}", e))?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio))))
+        .with_resource(Resource::builder().with_service_name(config.service_name.clone()).build())
+        .build();
+    global::set_tracer_provider(provider.clone());
+
+    Ok(Some(provider))
+}
+
+/// Capa de tracing que convierte los spans en spans de OpenTelemetry
+pub fn layer<S>(provider: &SdkTracerProvider, service_name: &str) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name.to_string()))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+struct RequestInjector(Vec<(String, String)>);
+
+impl Injector for RequestInjector {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.push((key.to_string(), value));
+    }
+}
+
+/// Contexto de traza recibido en los headers `traceparent`/`tracestate` (vacío si no vienen o son inválidos)
+pub fn extract_context(headers: &HeaderMap) -> opentelemetry::Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+/// Añade a una petición saliente `traceparent` (y `tracestate`) del span actual y el `x-request-id` del request en curso
+pub fn propagate_headers(mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    if let Some(id) = crate::middleware::request_id::current() {
+        request = request.header(crate::middleware::request_id::REQUEST_ID_HEADER, id);
+    }
+    let cx = tracing::Span::current().context();
+    let mut injector = RequestInjector(Vec::new());
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&cx, &mut injector));
+    for (key, value) in injector.0 {
+        request = request.header(key, value);
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn continues_incoming_traceparent_on_outgoing_requests() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(layer(&provider, "test"));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let incoming = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                actix_web::http::header::HeaderName::from_static("traceparent"),
+                actix_web::http::header::HeaderValue::from_static(incoming),
+            );
+            let parent = extract_context(&headers);
+
+            let span = tracing::info_span!("http_request");
+            span.set_parent(parent).unwrap();
+            let _guard = span.enter();
+            assert_eq!(
+                tracing::Span::current().context().span().span_context().trace_id().to_string(),
+                "4bf92f3577b34da6a3ce929d0e0e4736"
+            );
+
+            let request = propagate_headers(reqwest::Client::new().get("http://localhost/queue")).build().unwrap();
+            let traceparent = request.headers().get("traceparent").unwrap().to_str().unwrap();
+            assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+            assert!(!traceparent.contains("00f067aa0ba902b7"), "outgoing parent must be the local span");
+        });
+    }
+}
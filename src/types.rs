@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,9 +13,74 @@ pub struct JwtClaims {
 #[derive(Clone, Debug)]
 pub struct AuthContext {
     pub user_id: String,
-    #[allow(dead_code)] // Campo de dominio, puede usarse en el futuro
+    /// Usado para evaluar feature flags por tipo de cuenta
     pub account_type_id: Option<String>,
     pub session_id: Option<String>,
     pub business_id: String,
 }
 
+
+/// Tipo de dispositivo del cliente, inferido del modelo o del User-Agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceType {
+    Phone,
+    Tablet,
+}
+
+impl DeviceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceType::Phone => "phone",
+            DeviceType::Tablet => "tablet",
+        }
+    }
+
+    /// Valor del header `x-client-device-type` ("phone" o "tablet", sin distinguir mayúsculas)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "phone" | "mobile" => Some(DeviceType::Phone),
+            "tablet" => Some(DeviceType::Tablet),
+            _ => None,
+        }
+    }
+}
+
+/// Información del dispositivo del cliente
+/// Se construye a partir de `x-client-device`, `x-client-device-type`, `x-client-os`, `x-app-version` y `User-Agent`
+/// (ver `middleware::device`)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<DeviceType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+}
+
+/// Pista del cliente para elegir la variante de imagen (densidad de pantalla y ancho de render)
+/// Se obtiene de la query (`density`, `width`) o de los headers `x-client-density` / `x-client-image-width`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageHint {
+    /// Densidad redondeada hacia arriba y limitada a 1..=3 (@1x, @2x, @3x)
+    pub density: Option<u8>,
+    /// Ancho en píxeles lógicos con el que se va a mostrar la imagen
+    pub width: Option<u32>,
+}
+
+impl ImageHint {
+    pub fn is_empty(&self) -> bool {
+        self.density.is_none() && self.width.is_none()
+    }
+}
+
+/// ID de correlación del request (`x-request-id`), recibido del cliente o generado
+/// Se guarda en las extensions del request (ver `middleware::request_id`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);